@@ -0,0 +1,129 @@
+use simba::scalar::RealField;
+
+/// Solves the assignment problem for the given weights with the Hungarian algorithm.
+///
+/// The matrix needs to have at least as many columns as rows.
+/// Returns the column assigned to every row such that the sum of the weights is maximal.
+/// This implementation uses potentials and runs in `O(n^2 m)` for `n` rows and `m` columns.
+pub(crate) fn maximize_weight<F>(weights: &[Vec<F>]) -> Vec<usize>
+where
+    F: Copy + RealField,
+{
+    let n = weights.len();
+    let m = weights.first().map_or(0, |row| row.len());
+    assert!(
+        n <= m,
+        "assignment requires at least as many columns as rows"
+    );
+
+    // Indices are shifted by one such that row and column 0 act as virtual starting points.
+    let cost = |i: usize, j: usize| -weights[i - 1][j - 1];
+    let mut u = vec![F::zero(); n + 1];
+    let mut v = vec![F::zero(); m + 1];
+    let mut matched = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+    for i in 1..=n {
+        matched[0] = i;
+        let mut j0 = 0;
+        let mut min_v: Vec<Option<F>> = vec![None; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = matched[j0];
+            let mut delta: Option<F> = None;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let current = cost(i0, j) - u[i0] - v[j];
+                if min_v[j].is_none_or(|x| current < x) {
+                    min_v[j] = Some(current);
+                    way[j] = j0;
+                }
+                let candidate = min_v[j].unwrap();
+                if delta.is_none_or(|d| candidate < d) {
+                    delta = Some(candidate);
+                    j1 = j;
+                }
+            }
+            let delta = delta.unwrap();
+            for j in 0..=m {
+                if used[j] {
+                    u[matched[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] = min_v[j].map(|x| x - delta);
+                }
+            }
+            j0 = j1;
+            if matched[j0] == 0 {
+                break;
+            }
+        }
+        loop {
+            let j1 = way[j0];
+            matched[j0] = matched[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut res = vec![0; n];
+    for j in 1..=m {
+        if matched[j] != 0 {
+            res[matched[j] - 1] = j - 1;
+        }
+    }
+    res
+}
+
+#[test]
+fn maximize_weight_brute_force() {
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![vec![]];
+        }
+        permutations(n - 1)
+            .into_iter()
+            .flat_map(|p| {
+                (0..n).map(move |k| {
+                    let mut q = p.clone();
+                    q.insert(k, n - 1);
+                    q
+                })
+            })
+            .collect()
+    }
+    let mut state = 7u64;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as f64 / (1u64 << 31) as f64
+    };
+    for n in 1..7 {
+        let weights: Vec<Vec<f64>> = (0..n).map(|_| (0..n).map(|_| next()).collect()).collect();
+        let total = |assignment: &[usize]| -> f64 {
+            assignment
+                .iter()
+                .enumerate()
+                .map(|(i, &j)| weights[i][j])
+                .sum()
+        };
+        let best = permutations(n)
+            .iter()
+            .map(|p| total(p))
+            .fold(f64::MIN, f64::max);
+        let assignment = maximize_weight(&weights);
+        approx::assert_relative_eq!(total(&assignment), best, max_relative = 1e-12);
+    }
+}
+
+#[test]
+fn maximize_weight_rectangular() {
+    let weights = vec![vec![1.0, 5.0, 2.0, 0.0], vec![1.0, 4.0, 0.0, 3.0]];
+    assert_eq!(maximize_weight(&weights), vec![1, 3]);
+}
@@ -0,0 +1,380 @@
+use simba::scalar::RealField;
+
+use crate::{DecomposedDomain, Error, Rectangle, Result};
+
+/// Band structure of a decomposition of a rectangle
+///
+/// The rectangle is divided into bands which are stacked along one axis.
+/// Every band is then divided into subdomains along the other axis.
+/// All decompositions generated by [kmr_decompose](crate::kmr_decompose) have this structure.
+///
+/// ```text
+/// ┌───────────┬───────────┬───────────┐
+/// │           │           │           │ band 1
+/// ├────────┬──┴─────┬─────┴──┬────────┤
+/// │        │        │        │        │ band 0
+/// └────────┴────────┴────────┴────────┘
+/// ```
+///
+/// The layout can be used to [rebalance](BandLayout::rebalance) the decomposition by moving the
+/// shared boundaries between subdomains while keeping its topology intact.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "BandLayoutRaw<F>",
+        bound(deserialize = "F: Copy + PartialOrd + serde::Deserialize<'de>")
+    )
+)]
+pub struct BandLayout<F> {
+    axis: usize,
+    bands: Vec<F>,
+    cuts: Vec<Vec<F>>,
+    positions: Vec<(usize, usize)>,
+}
+
+/// Unchecked [BandLayout] which is validated after deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct BandLayoutRaw<F> {
+    axis: usize,
+    bands: Vec<F>,
+    cuts: Vec<Vec<F>>,
+    positions: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "serde")]
+impl<F> TryFrom<BandLayoutRaw<F>> for BandLayout<F>
+where
+    F: Copy + PartialOrd,
+{
+    type Error = Error;
+
+    /// Checks the invariants of [BandLayout::from_rectangles]
+    ///
+    /// Boundaries need to be sorted, all bands need to span the same range and every subdomain
+    /// needs to occupy a distinct slot such that all slots are occupied.
+    fn try_from(raw: BandLayoutRaw<F>) -> Result<Self> {
+        let BandLayoutRaw {
+            axis,
+            bands,
+            cuts,
+            positions,
+        } = raw;
+        let sorted = |boundaries: &[F]| boundaries.windows(2).all(|w| w[0] <= w[1]);
+        let first = cuts.first().ok_or(Error::Layout)?;
+        if axis > 1
+            || bands.len() != cuts.len() + 1
+            || !sorted(&bands)
+            || cuts.iter().any(|c| {
+                c.len() < 2
+                    || !sorted(c)
+                    || c[0] != first[0]
+                    || c[c.len() - 1] != first[first.len() - 1]
+            })
+        {
+            return Err(Error::Layout);
+        }
+        let mut occupied: Vec<Vec<bool>> = cuts.iter().map(|c| vec![false; c.len() - 1]).collect();
+        for &(band, slot) in positions.iter() {
+            match occupied.get_mut(band).and_then(|band| band.get_mut(slot)) {
+                Some(occupied @ false) => *occupied = true,
+                _ => return Err(Error::Layout),
+            }
+        }
+        if occupied.iter().flatten().any(|&o| !o) {
+            return Err(Error::Layout);
+        }
+        Ok(BandLayout {
+            axis,
+            bands,
+            cuts,
+            positions,
+        })
+    }
+}
+
+/// Moves the interior boundaries such that the loads of all segments become proportional to the
+/// given shares.
+///
+/// The load is assumed to be distributed uniformly within each segment.
+fn redistribute<F>(boundaries: &mut [F], loads: &[F], shares: &[F], relaxation: F)
+where
+    F: Copy + RealField,
+{
+    let total_load = loads.iter().fold(F::zero(), |acc, &l| acc + l);
+    let total_share = shares.iter().fold(F::zero(), |acc, &s| acc + s);
+    if total_load <= F::zero() {
+        return;
+    }
+    let old = boundaries.to_vec();
+    let mut share = F::zero();
+    let mut segment = 0;
+    let mut cumulative = F::zero();
+    for j in 1..boundaries.len() - 1 {
+        share += shares[j - 1];
+        let target = total_load * share / total_share;
+        while segment + 1 < loads.len() && cumulative + loads[segment] < target {
+            cumulative += loads[segment];
+            segment += 1;
+        }
+        let position = if loads[segment] > F::zero() {
+            let fraction = ((target - cumulative) / loads[segment])
+                .max(F::zero())
+                .min(F::one());
+            old[segment] + fraction * (old[segment + 1] - old[segment])
+        } else {
+            old[segment + 1]
+        };
+        boundaries[j] = old[j] + relaxation * (position - old[j]);
+    }
+}
+
+impl<F> BandLayout<F>
+where
+    F: Copy + RealField,
+{
+    fn from_rectangles_along(rectangles: &[Rectangle<F>], axis: usize) -> Option<Self> {
+        let other = 1 - axis;
+        let mut order: Vec<usize> = (0..rectangles.len()).collect();
+        order.sort_by(|&n, &m| {
+            let (r, s) = (&rectangles[n], &rectangles[m]);
+            (r.min[axis], r.min[other])
+                .partial_cmp(&(s.min[axis], s.min[other]))
+                .unwrap()
+        });
+
+        let mut bands = vec![rectangles[order[0]].min[axis]];
+        let mut cuts: Vec<Vec<F>> = Vec::new();
+        let mut positions = vec![(0, 0); rectangles.len()];
+        for &n in order.iter() {
+            let r = &rectangles[n];
+            if r.min[axis] == bands[bands.len() - 1] {
+                // Start a new band
+                bands.push(r.max[axis]);
+                cuts.push(vec![r.min[other], r.max[other]]);
+            } else {
+                let band = cuts.last_mut().unwrap();
+                if r.min[axis] != bands[bands.len() - 2]
+                    || r.max[axis] != bands[bands.len() - 1]
+                    || r.min[other] != band[band.len() - 1]
+                {
+                    return None;
+                }
+                band.push(r.max[other]);
+            }
+            let band = cuts.len() - 1;
+            positions[n] = (band, cuts[band].len() - 2);
+        }
+        let (lower, upper) = (cuts[0][0], cuts[0][cuts[0].len() - 1]);
+        if cuts
+            .iter()
+            .any(|c| c[0] != lower || c[c.len() - 1] != upper)
+        {
+            return None;
+        }
+        Some(BandLayout {
+            axis,
+            bands,
+            cuts,
+            positions,
+        })
+    }
+
+    /// Determines the band structure of the given subdomains.
+    ///
+    /// Subdomains need to share their boundaries exactly.
+    /// If the subdomains can be interpreted as bands along both axes, bands stacked along the
+    /// second axis are preferred.
+    /// Returns [Error::Layout] if the subdomains do not form a band structure.
+    pub fn from_rectangles(rectangles: &[Rectangle<F>]) -> Result<Self> {
+        if rectangles.is_empty() {
+            return Err(Error::Empty);
+        }
+        Self::from_rectangles_along(rectangles, 1)
+            .or_else(|| Self::from_rectangles_along(rectangles, 0))
+            .ok_or(Error::Layout)
+    }
+
+    /// Axis along which the bands are stacked
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Boundaries of all bands along [axis](BandLayout::axis)
+    pub fn band_boundaries(&self) -> &[F] {
+        &self.bands
+    }
+
+    /// Boundaries of the subdomains of every band along the other axis
+    pub fn cuts(&self) -> &[Vec<F>] {
+        &self.cuts
+    }
+
+    /// Subdomains in their original order
+    pub fn rectangles(&self) -> DecomposedDomain<F> {
+        let other = 1 - self.axis;
+        self.positions
+            .iter()
+            .map(|&(band, slot)| {
+                let mut rectangle = Rectangle {
+                    min: [F::zero(); 2],
+                    max: [F::zero(); 2],
+                };
+                rectangle.min[self.axis] = self.bands[band];
+                rectangle.max[self.axis] = self.bands[band + 1];
+                rectangle.min[other] = self.cuts[band][slot];
+                rectangle.max[other] = self.cuts[band][slot + 1];
+                rectangle
+            })
+            .collect()
+    }
+
+    /// Moves shared boundaries towards a state in which every subdomain carries the same load.
+    ///
+    /// The given loads are measured per subdomain in the original order and are assumed to be
+    /// distributed uniformly within each subdomain.
+    /// Boundaries between bands are moved such that every band carries a load proportional to
+    /// its number of subdomains and boundaries within each band are moved such that all its
+    /// subdomains carry the same load.
+    /// Every boundary is only moved by the fraction `relaxation` (between `0` and `1`) of the
+    /// distance to its target which dampens oscillations when loads are measured repeatedly.
+    /// The number of bands and subdomains per band never changes.
+    ///
+    /// ```
+    /// use spatial_decomposition::*;
+    ///
+    /// let domain = Rectangle {
+    ///     min: [0.0; 2],
+    ///     max: [100.0; 2],
+    /// };
+    /// let subdomains = kmr_decompose(&domain, 4.try_into().unwrap());
+    /// let mut layout = BandLayout::from_rectangles(&subdomains)?;
+    ///
+    /// // The load density is twice as high on the right half of the domain
+    /// let load = |r: &Rectangle<f64>| {
+    ///     let left = (r.max[0].min(50.0) - r.min[0]).max(0.0);
+    ///     let right = (r.max[0] - r.min[0].max(50.0)).max(0.0);
+    ///     (left + 2.0 * right) * (r.max[1] - r.min[1])
+    /// };
+    /// for _ in 0..20 {
+    ///     let loads: Vec<_> = layout.rectangles().iter().map(load).collect();
+    ///     layout.rebalance(&loads, 0.8)?;
+    /// }
+    /// for subdomain in layout.rectangles() {
+    ///     approx::assert_relative_eq!(load(&subdomain), 15000.0 / 4.0, max_relative = 1e-6);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn rebalance(&mut self, loads: &[F], relaxation: F) -> Result<()> {
+        if loads.len() != self.positions.len()
+            || loads.iter().any(|l| !l.is_finite() || *l < F::zero())
+            || !(relaxation > F::zero() && relaxation <= F::one())
+        {
+            return Err(Error::Load);
+        }
+        let mut band_loads: Vec<Vec<F>> = self
+            .cuts
+            .iter()
+            .map(|c| vec![F::zero(); c.len() - 1])
+            .collect();
+        for (&(band, slot), &load) in self.positions.iter().zip(loads) {
+            band_loads[band][slot] = load;
+        }
+
+        let totals: Vec<F> = band_loads
+            .iter()
+            .map(|l| l.iter().fold(F::zero(), |acc, &x| acc + x))
+            .collect();
+        let shares: Vec<F> = band_loads
+            .iter()
+            .map(|l| F::from_usize(l.len()).unwrap())
+            .collect();
+        redistribute(&mut self.bands, &totals, &shares, relaxation);
+        for (cuts, loads) in self.cuts.iter_mut().zip(band_loads.iter()) {
+            let shares = vec![F::one(); loads.len()];
+            redistribute(cuts, loads, &shares, relaxation);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn band_layout_roundtrip() {
+    let domain = Rectangle {
+        min: [-3.0, 2.0],
+        max: [97.0, 102.0],
+    };
+    for n in [1, 2, 3, 5, 7, 9, 14, 23, 50] {
+        let subdomains = crate::kmr_decompose(&domain, n.try_into().unwrap());
+        let layout = BandLayout::from_rectangles(&subdomains).unwrap();
+        assert_eq!(layout.rectangles(), subdomains);
+        let n_subdomains: usize = layout.cuts().iter().map(|c| c.len() - 1).sum();
+        assert_eq!(n_subdomains, n);
+    }
+}
+
+#[test]
+fn band_layout_invalid() {
+    // Pinwheel arrangement without band structure
+    let rectangles = [
+        Rectangle {
+            min: [0.0, 0.0],
+            max: [2.0, 1.0],
+        },
+        Rectangle {
+            min: [2.0, 0.0],
+            max: [3.0, 2.0],
+        },
+        Rectangle {
+            min: [1.0, 2.0],
+            max: [3.0, 3.0],
+        },
+        Rectangle {
+            min: [0.0, 1.0],
+            max: [1.0, 3.0],
+        },
+        Rectangle {
+            min: [1.0, 1.0],
+            max: [2.0, 2.0],
+        },
+    ];
+    assert!(BandLayout::from_rectangles(&rectangles).is_err());
+    assert!(BandLayout::<f64>::from_rectangles(&[]).is_err());
+}
+
+#[test]
+fn band_layout_rebalance_converges() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [200.0, 100.0],
+    };
+    // Linearly increasing load density
+    let load = |r: &Rectangle<f64>| {
+        let center = r.center();
+        r.volume() * (1.0 + 3.0 * center[0] / 200.0 + center[1] / 100.0)
+    };
+    let subdomains = crate::kmr_decompose(&domain, 11.try_into().unwrap());
+    let mut layout = BandLayout::from_rectangles(&subdomains).unwrap();
+    let imbalance = |layout: &BandLayout<f64>| {
+        let loads: Vec<_> = layout.rectangles().iter().map(load).collect();
+        let max = loads.iter().cloned().fold(f64::MIN, f64::max);
+        let min = loads.iter().cloned().fold(f64::MAX, f64::min);
+        max / min
+    };
+    let initial = imbalance(&layout);
+    for _ in 0..50 {
+        let loads: Vec<_> = layout.rectangles().iter().map(load).collect();
+        layout.rebalance(&loads, 0.5).unwrap();
+    }
+    assert!(initial > 1.5);
+    assert!(imbalance(&layout) < 1.001);
+
+    // The topology is unchanged and the domain is still covered
+    let rebalanced = layout.rectangles();
+    assert_eq!(rebalanced.len(), subdomains.len());
+    let area: f64 = rebalanced.iter().map(|r| r.volume()).sum();
+    approx::assert_relative_eq!(area, domain.volume(), max_relative = 1e-12);
+    assert!(layout.rebalance(&[1.0; 3], 0.5).is_err());
+}
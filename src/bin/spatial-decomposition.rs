@@ -0,0 +1,365 @@
+use std::io::Write;
+use std::num::NonZeroUsize;
+
+use spatial_decomposition::*;
+
+const USAGE: &str = "\
+Decompose rectangular domains into subdomains
+
+Usage: spatial-decomposition <COMMAND> [OPTIONS]
+
+Commands:
+  decompose  Split the domain into subdomains
+  digitize   Split the domain into subdomains and assign a regular grid of digits to them
+
+Options:
+  -d, --domain <X0,Y0,X1,Y1>  Lower and upper bounds of the domain
+  -p, --subdomains <N>        Number of subdomains (not together with --levels)
+  -l, --levels <N,M,...>      Decompose hierarchically with the given number of subdomains per level
+  -g, --grid <NX,NY>          Number of digits along each axis (digitize only)
+  -i, --input <FILE>          Read settings from a JSON file (`-` for stdin)
+  -f, --format <FORMAT>       Output format: json, csv, svg or vtk [default: json]
+  -o, --output <FILE>         Write to file instead of stdout
+  -h, --help                  Print this message
+
+Settings given as arguments take precedence over the JSON input which may contain the keys
+`domain` ({\"min\": [X0, Y0], \"max\": [X1, Y1]}), `subdomains`, `levels` and `grid`.
+Giving --subdomains or --levels as argument replaces both keys of the input.";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Command {
+    Decompose,
+    Digitize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+    Svg,
+    Vtk,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Settings {
+    domain: Option<Rectangle<f64>>,
+    subdomains: Option<usize>,
+    levels: Option<Vec<usize>>,
+    grid: Option<[usize; 2]>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Arguments {
+    command: Command,
+    settings: Settings,
+    input: Option<String>,
+    format: Format,
+    output: Option<String>,
+}
+
+type BoxedResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn parse_list<T: std::str::FromStr>(value: &str, name: &str) -> BoxedResult<Vec<T>> {
+    value
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .map_err(|_| format!("invalid value `{x}` for {name}").into())
+        })
+        .collect()
+}
+
+fn parse_arguments(args: &[String]) -> BoxedResult<Option<Arguments>> {
+    let mut command = None;
+    let mut settings = Settings::default();
+    let mut input = None;
+    let mut format = Format::Json;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "decompose" if command.is_none() => command = Some(Command::Decompose),
+            "digitize" if command.is_none() => command = Some(Command::Digitize),
+            "-d" | "--domain" => {
+                let bounds: Vec<f64> = parse_list(value()?, "domain")?;
+                let [x0, y0, x1, y1] = bounds[..] else {
+                    return Err("domain requires exactly four values".into());
+                };
+                settings.domain = Some(Rectangle {
+                    min: [x0, y0],
+                    max: [x1, y1],
+                });
+            }
+            "-p" | "--subdomains" => {
+                let subdomains: Vec<usize> = parse_list(value()?, arg)?;
+                let [n] = subdomains[..] else {
+                    return Err("subdomains requires exactly one value".into());
+                };
+                settings.subdomains = Some(n);
+            }
+            "-l" | "--levels" => settings.levels = Some(parse_list(value()?, arg)?),
+            "-g" | "--grid" => {
+                let grid: Vec<usize> = parse_list(value()?, arg)?;
+                let [nx, ny] = grid[..] else {
+                    return Err("grid requires exactly two values".into());
+                };
+                settings.grid = Some([nx, ny]);
+            }
+            "-i" | "--input" => input = Some(value()?.clone()),
+            "-f" | "--format" => {
+                format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    "svg" => Format::Svg,
+                    "vtk" | "vtu" => Format::Vtk,
+                    f => return Err(format!("unknown format `{f}`").into()),
+                }
+            }
+            "-o" | "--output" => output = Some(value()?.clone()),
+            _ => return Err(format!("unexpected argument `{arg}`").into()),
+        }
+    }
+    let command = command.ok_or("missing command")?;
+    Ok(Some(Arguments {
+        command,
+        settings,
+        input,
+        format,
+        output,
+    }))
+}
+
+/// Combines the settings of the arguments with the settings of the JSON input
+fn merge(arguments: Settings, input: Option<&str>) -> BoxedResult<Settings> {
+    let input: Settings = match input {
+        Some(json) => serde_json::from_str(json)?,
+        None => Settings::default(),
+    };
+    // The number of subdomains and the levels describe the same setting
+    let (subdomains, levels) = match (arguments.subdomains, arguments.levels) {
+        (None, None) => (input.subdomains, input.levels),
+        given => given,
+    };
+    if subdomains.is_some() && levels.is_some() {
+        return Err("subdomains and levels cannot be given together".into());
+    }
+    Ok(Settings {
+        domain: arguments.domain.or(input.domain),
+        subdomains,
+        levels,
+        grid: arguments.grid.or(input.grid),
+    })
+}
+
+fn non_zero(n: usize, name: &str) -> BoxedResult<NonZeroUsize> {
+    NonZeroUsize::new(n).ok_or_else(|| format!("{name} must be positive").into())
+}
+
+fn decompose(settings: &Settings) -> BoxedResult<DecomposedDomain<f64>> {
+    let domain = settings.domain.as_ref().ok_or("missing domain")?;
+    if !(domain.min[0] < domain.max[0] && domain.min[1] < domain.max[1]) {
+        return Err("domain must have positive extent".into());
+    }
+    match (&settings.levels, settings.subdomains) {
+        (Some(levels), _) => {
+            let levels = levels
+                .iter()
+                .map(|&n| non_zero(n, "levels"))
+                .collect::<BoxedResult<Vec<_>>>()?;
+            Ok(decompose_hierarchical(domain, &levels).flatten())
+        }
+        (None, Some(n)) => Ok(kmr_decompose(domain, non_zero(n, "subdomains")?)),
+        (None, None) => Err("missing number of subdomains or levels".into()),
+    }
+}
+
+fn grid_digits(domain: &Rectangle<f64>, grid: [usize; 2]) -> Vec<(usize, Rectangle<f64>)> {
+    let [nx, ny] = grid;
+    let dx = (domain.max[0] - domain.min[0]) / nx as f64;
+    let dy = (domain.max[1] - domain.min[1]) / ny as f64;
+    (0..nx * ny)
+        .map(|n| {
+            let (i, j) = (n % nx, n / nx);
+            let x = |i: usize| {
+                if i == nx {
+                    domain.max[0]
+                } else {
+                    domain.min[0] + i as f64 * dx
+                }
+            };
+            let y = |j: usize| {
+                if j == ny {
+                    domain.max[1]
+                } else {
+                    domain.min[1] + j as f64 * dy
+                }
+            };
+            (
+                n,
+                Rectangle {
+                    min: [x(i), y(j)],
+                    max: [x(i + 1), y(j + 1)],
+                },
+            )
+        })
+        .collect()
+}
+
+fn run(arguments: &Arguments, input: Option<&str>, out: &mut impl Write) -> BoxedResult<()> {
+    let settings = merge(arguments.settings.clone(), input)?;
+    let subdomains = decompose(&settings)?;
+    match arguments.command {
+        Command::Decompose => match arguments.format {
+            Format::Json => serde_json::to_writer_pretty(&mut *out, &subdomains)?,
+            Format::Csv => {
+                writeln!(out, "subdomain,x0,y0,x1,y1")?;
+                for (n, r) in subdomains.iter().enumerate() {
+                    writeln!(
+                        out,
+                        "{n},{},{},{},{}",
+                        r.min[0], r.min[1], r.max[0], r.max[1]
+                    )?;
+                }
+            }
+            Format::Svg => write!(
+                out,
+                "{}",
+                decomposition_svg(&subdomains, &SvgOptions::default())
+            )?,
+            Format::Vtk => write_vtu(&mut *out, &subdomains, None)?,
+        },
+        Command::Digitize => {
+            let grid = settings.grid.ok_or("missing grid")?;
+            if grid.contains(&0) {
+                return Err("grid must be positive".into());
+            }
+            let domain = settings.domain.as_ref().unwrap();
+            let digits = digitize(subdomains, grid_digits(domain, grid))?;
+            match arguments.format {
+                Format::Json => serde_json::to_writer_pretty(&mut *out, &digits)?,
+                Format::Csv => {
+                    writeln!(out, "subdomain,digit,x0,y0,x1,y1")?;
+                    for (n, (_, digits)) in digits.iter().enumerate() {
+                        for (i, r) in digits.iter() {
+                            writeln!(
+                                out,
+                                "{n},{i},{},{},{},{}",
+                                r.min[0], r.min[1], r.max[0], r.max[1]
+                            )?;
+                        }
+                    }
+                }
+                Format::Svg => {
+                    write!(out, "{}", digitization_svg(&digits, &SvgOptions::default()))?
+                }
+                Format::Vtk => {
+                    // Subdomains with the number of their digits as load
+                    let loads: Vec<f64> = digits.iter().map(|(_, d)| d.len() as f64).collect();
+                    let subdomains: Vec<_> = digits.into_iter().map(|(s, _)| s).collect();
+                    write_vtu(&mut *out, &subdomains, Some(&loads))?;
+                }
+            }
+        }
+    }
+    if arguments.format == Format::Json {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = parse_arguments(&args).and_then(|arguments| {
+        let Some(arguments) = arguments else {
+            println!("{USAGE}");
+            return Ok(());
+        };
+        let input = match arguments.input.as_deref() {
+            Some("-") => Some(std::io::read_to_string(std::io::stdin())?),
+            Some(path) => Some(std::fs::read_to_string(path)?),
+            None => None,
+        };
+        match &arguments.output {
+            Some(path) => {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                run(&arguments, input.as_deref(), &mut file)?;
+                file.flush()?;
+            }
+            None => run(&arguments, input.as_deref(), &mut std::io::stdout().lock())?,
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        eprintln!("error: {e}\n\n{USAGE}");
+        std::process::exit(2);
+    }
+}
+
+#[cfg(test)]
+fn run_to_string(args: &str, input: Option<&str>) -> BoxedResult<String> {
+    let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+    let arguments = parse_arguments(&args)?.unwrap();
+    let mut out = Vec::new();
+    run(&arguments, input, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn cli_decompose_json() {
+    let json = run_to_string("decompose --domain 0,0,90,20 -p 3", None).unwrap();
+    let subdomains: DecomposedDomain<f64> = serde_json::from_str(&json).unwrap();
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [90.0, 20.0],
+    };
+    assert_eq!(subdomains, kmr_decompose(&domain, 3.try_into().unwrap()));
+}
+
+#[test]
+fn cli_input_and_formats() {
+    let input =
+        r#"{"domain": {"min": [0, 0], "max": [100, 100]}, "subdomains": 4, "grid": [4, 4]}"#;
+    let csv = run_to_string("decompose -f csv", Some(input)).unwrap();
+    assert_eq!(csv.lines().count(), 5);
+    assert_eq!(csv.lines().nth(1), Some("0,0,0,50,50"));
+
+    // Arguments override the input
+    let csv = run_to_string("digitize -p 2 -f csv", Some(input)).unwrap();
+    assert_eq!(csv.lines().count(), 17);
+    let svg = run_to_string("digitize -f svg", Some(input)).unwrap();
+    assert_eq!(svg.matches("<rect").count(), 21);
+    let vtu = run_to_string("digitize --format vtk", Some(input)).unwrap();
+    assert!(vtu.contains(r#"Name="load""#));
+    // Levels given as argument replace the number of subdomains of the input
+    let levels = run_to_string("decompose -l 2,3 -f csv", Some(input)).unwrap();
+    assert_eq!(levels.lines().count(), 7);
+
+    // Subdomains given as argument replace the levels of the input
+    let input = r#"{"domain": {"min": [0, 0], "max": [100, 100]}, "levels": [2, 3]}"#;
+    let csv = run_to_string("decompose -p 2 -f csv", Some(input)).unwrap();
+    assert_eq!(csv.lines().count(), 3);
+}
+
+#[test]
+fn cli_errors() {
+    assert!(run_to_string("decompose -p 3", None).is_err());
+    assert!(run_to_string("decompose -d 0,0,1 -p 3", None).is_err());
+    assert!(run_to_string("decompose -d 0,0,100,100 -p 0", None).is_err());
+    assert!(run_to_string("digitize -d 0,0,100,100 -p 2", None).is_err());
+    assert!(run_to_string("decompose -d 0,0,100,100 -p 2 -f png", None).is_err());
+    assert!(run_to_string("-p 2", None).is_err());
+    assert!(run_to_string("decompose -d 0,0,100,100 -p 2,3", None).is_err());
+    assert!(run_to_string("decompose -d 0,0,100,100 -p 2 -l 2,3", None).is_err());
+    assert!(run_to_string("decompose", Some(r#"{"domains": 1}"#)).is_err());
+    let input = r#"{"domain": {"min": [0, 0], "max": [1, 1]}, "subdomains": 2, "levels": [2]}"#;
+    assert!(run_to_string("decompose", Some(input)).is_err());
+    assert!(parse_arguments(&["--help".to_string()]).unwrap().is_none());
+}
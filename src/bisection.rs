@@ -0,0 +1,118 @@
+use num_traits::AsPrimitive;
+use simba::scalar::RealField;
+
+use crate::Cuboid;
+
+/// Finds the coordinate along `axis` such that the volume of all pieces below it equals `target`.
+fn find_cut<F, const D: usize>(pieces: &[Cuboid<F, D>], axis: usize, target: F) -> F
+where
+    F: Copy + RealField,
+{
+    // The volume below a coordinate is piecewise linear with kinks at the piece boundaries.
+    let mut breakpoints: Vec<F> = pieces
+        .iter()
+        .flat_map(|p| [p.min[axis], p.max[axis]])
+        .collect();
+    breakpoints.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    breakpoints.dedup();
+
+    let volume_below = |x: F| {
+        pieces.iter().fold(F::zero(), |acc, p| {
+            let length = p.max[axis] - p.min[axis];
+            if length <= F::zero() {
+                return acc;
+            }
+            let covered = (x - p.min[axis]).max(F::zero()).min(length);
+            acc + p.volume() / length * covered
+        })
+    };
+
+    let mut lower = breakpoints[0];
+    let mut volume_lower = F::zero();
+    for &upper in breakpoints.iter().skip(1) {
+        let volume_upper = volume_below(upper);
+        if volume_upper >= target {
+            let slope = (volume_upper - volume_lower) / (upper - lower);
+            return (lower + (target - volume_lower) / slope).min(upper);
+        }
+        lower = upper;
+        volume_lower = volume_upper;
+    }
+    lower
+}
+
+/// Splits the given disjoint pieces into `n_subdomains` groups of equal volume.
+///
+/// This is an orthogonal recursive bisection: the bounding box of all pieces is cut
+/// perpendicular to its longest axis such that the volumes on both sides are proportional to the
+/// number of subdomains assigned to them.
+/// Pieces which are crossed by a cut are split.
+pub(crate) fn bisect<F, const D: usize>(
+    pieces: Vec<Cuboid<F, D>>,
+    n_subdomains: usize,
+) -> Vec<Vec<Cuboid<F, D>>>
+where
+    F: 'static + Copy + RealField,
+    usize: AsPrimitive<F>,
+{
+    if n_subdomains <= 1 || pieces.is_empty() {
+        return vec![pieces];
+    }
+
+    let lengths = pieces
+        .iter()
+        .fold(pieces[0].clone(), |acc, p| acc.bounding_box(p))
+        .lengths();
+    let axis = (0..D).fold(
+        0,
+        |axis, i| {
+            if lengths[i] > lengths[axis] { i } else { axis }
+        },
+    );
+
+    let n_lower = n_subdomains / 2;
+    let total = pieces.iter().fold(F::zero(), |acc, p| acc + p.volume());
+    let fraction: F = n_lower.as_() / n_subdomains.as_();
+    let cut = find_cut(&pieces, axis, total * fraction);
+
+    let mut lower = Vec::new();
+    let mut upper = Vec::new();
+    for p in pieces.into_iter() {
+        if p.max[axis] <= cut {
+            lower.push(p);
+        } else if p.min[axis] >= cut {
+            upper.push(p);
+        } else if let Some((p_lower, p_upper)) = p.split(axis, cut) {
+            lower.push(p_lower);
+            upper.push(p_upper);
+        }
+    }
+
+    let mut res = bisect(lower, n_lower);
+    res.extend(bisect(upper, n_subdomains - n_lower));
+    res
+}
+
+#[test]
+fn bisect_equal_volume() {
+    use approx::assert_relative_eq;
+    // L-shaped domain consisting of two rectangles
+    let pieces = vec![
+        Cuboid {
+            min: [0.0, 0.0],
+            max: [30.0, 10.0],
+        },
+        Cuboid {
+            min: [0.0, 10.0],
+            max: [10.0, 30.0],
+        },
+    ];
+    for n in 1..20 {
+        let groups = bisect(pieces.clone(), n);
+        assert_eq!(groups.len(), n);
+        for group in groups {
+            let v: f64 = group.iter().map(|p| p.volume()).sum();
+            assert_relative_eq!(v, 500.0 / n as f64, max_relative = 1e-10);
+        }
+    }
+}
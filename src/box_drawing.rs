@@ -0,0 +1,210 @@
+use num_traits::{AsPrimitive, Num};
+
+use crate::Rectangle;
+
+/// Renders subdomains as Unicode box-drawing diagram
+///
+/// The diagram is scaled to the given number of characters per line.
+/// Since characters are about twice as high as wide, every line covers twice the length of a
+/// single character.
+/// The `y`-axis points upwards such that subdomains with small `y` values appear at the bottom.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 70.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 7.try_into().unwrap());
+/// let diagram = BoxDrawing::new(&subdomains).width(31).labels(true);
+/// assert_eq!(
+///     diagram.to_string(),
+///     "\
+/// ┌─────────┬─────────┬─────────┐
+/// │         │         │         │
+/// │    4    │    5    │    6    │
+/// │         │         │         │
+/// │         │         │         │
+/// ├───────┬─┴────┬────┴──┬──────┤
+/// │       │      │       │      │
+/// │       │      │       │      │
+/// │   0   │  1   │   2   │  3   │
+/// │       │      │       │      │
+/// │       │      │       │      │
+/// └───────┴──────┴───────┴──────┘"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct BoxDrawing<'a, F> {
+    subdomains: &'a [Rectangle<F>],
+    width: usize,
+    labels: bool,
+}
+
+impl<'a, F> BoxDrawing<'a, F>
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+{
+    /// Creates a diagram which is 80 characters wide and does not contain labels.
+    pub fn new(subdomains: &'a [Rectangle<F>]) -> Self {
+        BoxDrawing {
+            subdomains,
+            width: 80,
+            labels: false,
+        }
+    }
+
+    /// Sets the number of characters per line
+    ///
+    /// Values below 2 are increased to 2.
+    pub fn width(self, width: usize) -> Self {
+        BoxDrawing {
+            width: width.max(2),
+            ..self
+        }
+    }
+
+    /// Writes the index of every subdomain at its center if there is enough space
+    pub fn labels(self, labels: bool) -> Self {
+        BoxDrawing { labels, ..self }
+    }
+}
+
+/// Character at a grid point with lines leaving to the left, right, top and bottom
+fn junction(left: bool, right: bool, up: bool, down: bool) -> char {
+    match (left, right, up, down) {
+        (false, false, false, false) => ' ',
+        (_, _, false, false) => '─',
+        (false, false, _, _) => '│',
+        (false, true, false, true) => '┌',
+        (true, false, false, true) => '┐',
+        (false, true, true, false) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '┬',
+        (true, true, true, false) => '┴',
+        (false, true, true, true) => '├',
+        (true, false, true, true) => '┤',
+        (true, true, true, true) => '┼',
+    }
+}
+
+impl<F> std::fmt::Display for BoxDrawing<'_, F>
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(first) = self.subdomains.first() else {
+            return Ok(());
+        };
+        let bounds = self
+            .subdomains
+            .iter()
+            .fold(first.clone(), |acc, r| acc.bounding_box(r));
+        let min: [f64; 2] = bounds.min.map(|x| x.as_());
+        let max: [f64; 2] = bounds.max.map(|x| x.as_());
+        let n_cols = self.width;
+        let n_rows = if max[0] > min[0] {
+            ((max[1] - min[1]) / (max[0] - min[0]) * (n_cols - 1) as f64 / 2.0).round() as usize + 1
+        } else {
+            n_cols / 2
+        }
+        .max(2);
+
+        let column = |x: F| {
+            let t = (x.as_() - min[0]) / (max[0] - min[0]);
+            (t * (n_cols - 1) as f64).round() as usize
+        };
+        let row = |y: F| {
+            let t = (max[1] - y.as_()) / (max[1] - min[1]);
+            (t * (n_rows - 1) as f64).round() as usize
+        };
+
+        // Horizontal segments right of and vertical segments below every grid point
+        let mut horizontal = vec![vec![false; n_cols]; n_rows];
+        let mut vertical = vec![vec![false; n_cols]; n_rows];
+        let mut labels = Vec::new();
+        for (n, rectangle) in self.subdomains.iter().enumerate() {
+            let (c0, c1) = (column(rectangle.min[0]), column(rectangle.max[0]));
+            let (r0, r1) = (row(rectangle.max[1]), row(rectangle.min[1]));
+            horizontal[r0][c0..c1].fill(true);
+            horizontal[r1][c0..c1].fill(true);
+            for v in vertical[r0..r1].iter_mut() {
+                v[c0] = true;
+                v[c1] = true;
+            }
+            let label = n.to_string();
+            if self.labels && r1 > r0 + 1 && c1 > c0 + label.len() {
+                let c = (c0 + c1 + 1 - label.len()) / 2;
+                labels.push(((r0 + r1) / 2, c, label));
+            }
+        }
+
+        let mut lines: Vec<Vec<char>> = (0..n_rows)
+            .map(|r| {
+                (0..n_cols)
+                    .map(|c| {
+                        junction(
+                            c > 0 && horizontal[r][c - 1],
+                            horizontal[r][c],
+                            r > 0 && vertical[r - 1][c],
+                            vertical[r][c],
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        for (r, c, label) in labels {
+            for (i, ch) in label.chars().enumerate() {
+                lines[r][c + i] = ch;
+            }
+        }
+        for (r, line) in lines.iter().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", line.iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn box_drawing_grid() {
+    let subdomains: Vec<_> = (0..4)
+        .map(|n| Rectangle {
+            min: [n % 2 * 10, n / 2 * 8],
+            max: [n % 2 * 10 + 10, n / 2 * 8 + 8],
+        })
+        .collect();
+    let diagram = BoxDrawing::new(&subdomains).width(11).to_string();
+    assert_eq!(
+        diagram,
+        "\
+┌────┬────┐
+│    │    │
+├────┼────┤
+│    │    │
+└────┴────┘"
+    );
+}
+
+#[test]
+fn box_drawing_labels_need_space() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 16.try_into().unwrap());
+    let wide = BoxDrawing::new(&subdomains).labels(true).to_string();
+    for n in 0..16 {
+        assert!(wide.contains(&format!(" {n} ")));
+    }
+    assert!(wide.lines().all(|l| l.chars().count() <= 80));
+    let narrow = BoxDrawing::new(&subdomains)
+        .width(9)
+        .labels(true)
+        .to_string();
+    assert!(!narrow.chars().any(|c| c.is_ascii_digit()));
+    assert_eq!(BoxDrawing::<f64>::new(&[]).to_string(), "");
+}
@@ -0,0 +1,189 @@
+use num_traits::Num;
+
+use crate::{Cuboid, Error, KdTree, Result};
+
+/// Union of disjoint [Cuboid]s
+///
+/// Subdomains of non-rectangular domains can in general not be represented by a single
+/// [Cuboid].
+/// This type is returned by decomposition methods such as
+/// [rectilinear_decompose](crate::rectilinear_decompose) and
+/// [decompose_with_holes](crate::decompose_with_holes).
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// // L-shaped domain
+/// let domain = CompositeDomain::new(vec![
+///     Rectangle {
+///         min: [0.0, 0.0],
+///         max: [2.0, 1.0],
+///     },
+///     Rectangle {
+///         min: [0.0, 1.0],
+///         max: [1.0, 2.0],
+///     },
+/// ])?;
+/// assert_eq!(domain.volume(), 3.0);
+/// assert!(domain.contains(&[0.5, 1.5]));
+/// assert!(!domain.contains(&[1.5, 1.5]));
+/// assert_eq!(
+///     domain.bounding_box(),
+///     Some(Rectangle {
+///         min: [0.0; 2],
+///         max: [2.0; 2],
+///     })
+/// );
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "CompositeDomainRaw<F, D>",
+        bound(deserialize = "F: Copy + PartialOrd + Num + serde::Deserialize<'de>")
+    )
+)]
+pub struct CompositeDomain<F, const D: usize> {
+    pieces: Vec<Cuboid<F, D>>,
+}
+
+/// Unchecked [CompositeDomain] which is validated by [CompositeDomain::new] after deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "F: serde::Deserialize<'de>"))]
+struct CompositeDomainRaw<F, const D: usize> {
+    pieces: Vec<Cuboid<F, D>>,
+}
+
+#[cfg(feature = "serde")]
+impl<F, const D: usize> TryFrom<CompositeDomainRaw<F, D>> for CompositeDomain<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    type Error = Error;
+
+    fn try_from(raw: CompositeDomainRaw<F, D>) -> Result<Self> {
+        CompositeDomain::new(raw.pieces)
+    }
+}
+
+/// Subdomains which consist of one or multiple disjoint [Cuboid]s
+///
+/// This trait allows digitization methods to work with single [Cuboid]s as well as with
+/// [CompositeDomain]s.
+pub trait Subdomain<F, const D: usize> {
+    /// Disjoint cuboids whose union forms the subdomain
+    fn pieces(&self) -> &[Cuboid<F, D>];
+}
+
+impl<F, const D: usize> Subdomain<F, D> for Cuboid<F, D> {
+    fn pieces(&self) -> &[Cuboid<F, D>] {
+        std::slice::from_ref(self)
+    }
+}
+
+impl<F, const D: usize> Subdomain<F, D> for CompositeDomain<F, D> {
+    fn pieces(&self) -> &[Cuboid<F, D>] {
+        &self.pieces
+    }
+}
+
+impl<F, const D: usize> From<Cuboid<F, D>> for CompositeDomain<F, D> {
+    fn from(cuboid: Cuboid<F, D>) -> Self {
+        CompositeDomain {
+            pieces: vec![cuboid],
+        }
+    }
+}
+
+impl<F, const D: usize> CompositeDomain<F, D> {
+    /// Constructs the domain without checking that the pieces are disjoint.
+    pub(crate) fn from_disjoint(pieces: Vec<Cuboid<F, D>>) -> Self {
+        CompositeDomain { pieces }
+    }
+
+    /// Disjoint cuboids whose union forms this domain
+    pub fn pieces(&self) -> &[Cuboid<F, D>] {
+        &self.pieces
+    }
+
+    /// Returns the underlying cuboids
+    pub fn into_pieces(self) -> Vec<Cuboid<F, D>> {
+        self.pieces
+    }
+}
+
+impl<F, const D: usize> CompositeDomain<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    /// Constructs a new domain from the given pieces.
+    ///
+    /// Pieces may touch each other but must not overlap.
+    /// Otherwise, [Error::Overlap] is returned.
+    pub fn new(pieces: Vec<Cuboid<F, D>>) -> Result<Self> {
+        let tree = KdTree::new(pieces);
+        for (n, piece) in tree.cuboids().iter().enumerate() {
+            for m in tree.intersecting(piece) {
+                let overlap = piece.intersection(&tree.cuboids()[m]);
+                if m != n && overlap.is_some_and(|c| (0..D).all(|i| c.min[i] < c.max[i])) {
+                    return Err(Error::Overlap);
+                }
+            }
+        }
+        Ok(CompositeDomain {
+            pieces: tree.cuboids().to_vec(),
+        })
+    }
+
+    /// Total volume of all pieces
+    pub fn volume(&self) -> F {
+        self.pieces
+            .iter()
+            .fold(F::zero(), |acc, p| acc + p.volume())
+    }
+
+    /// Checks if the point is contained in any of the pieces (bounds inclusive)
+    pub fn contains(&self, point: &[F; D]) -> bool {
+        self.pieces.iter().any(|p| p.contains_point(point))
+    }
+
+    /// Smallest [Cuboid] which contains all pieces
+    ///
+    /// Returns [None] if the domain is empty.
+    pub fn bounding_box(&self) -> Option<Cuboid<F, D>> {
+        let mut pieces = self.pieces.iter();
+        let first = pieces.next()?.clone();
+        Some(pieces.fold(first, |acc, p| acc.bounding_box(p)))
+    }
+}
+
+#[test]
+fn composite_domain_overlap() {
+    let c1 = Cuboid {
+        min: [0.0; 3],
+        max: [1.0; 3],
+    };
+    let c2 = Cuboid {
+        min: [0.5; 3],
+        max: [1.5; 3],
+    };
+    let c3 = Cuboid {
+        min: [1.0, 0.0, 0.0],
+        max: [2.0, 1.0, 1.0],
+    };
+    assert!(CompositeDomain::new(vec![c1.clone(), c2]).is_err());
+    let domain = CompositeDomain::new(vec![c1, c3]).unwrap();
+    assert_eq!(domain.volume(), 2.0);
+    assert_eq!(domain.pieces().len(), 2);
+}
+
+#[test]
+fn composite_domain_empty() {
+    let domain = CompositeDomain::<f32, 2>::new(Vec::new()).unwrap();
+    assert_eq!(domain.volume(), 0.0);
+    assert_eq!(domain.bounding_box(), None);
+    assert!(!domain.contains(&[0.0; 2]));
+}
@@ -0,0 +1,265 @@
+use num_traits::Num;
+
+use crate::Cuboid;
+
+/// Geometric queries and operations
+///
+/// All methods only require basic arithmetic such that they can also be used with integer
+/// coordinates.
+/// Bounds are treated inclusively.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let c = Cuboid {
+///     min: [0.0, 1.0, 2.0],
+///     max: [2.0, 3.0, 6.0],
+/// };
+/// assert_eq!(c.volume(), 16.0);
+/// assert_eq!(c.surface(), 2.0 * (4.0 + 8.0 + 8.0));
+/// assert_eq!(c.center(), [1.0, 2.0, 4.0]);
+/// assert!(c.contains_point(&[2.0, 1.0, 3.0]));
+/// assert_eq!(c.corners().count(), 8);
+/// ```
+impl<F, const D: usize> Cuboid<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    /// Side lengths along every axis
+    pub fn lengths(&self) -> [F; D] {
+        std::array::from_fn(|i| self.max[i] - self.min[i])
+    }
+
+    /// Volume (area in `2D`, length in `1D`) of the cuboid
+    pub fn volume(&self) -> F {
+        self.lengths().into_iter().fold(F::one(), |acc, l| acc * l)
+    }
+
+    /// Measure of the surface (perimeter in `2D`) of the cuboid
+    ///
+    /// In `1D` this is the number of end points.
+    pub fn surface(&self) -> F {
+        let lengths = self.lengths();
+        let faces = (0..D).fold(F::zero(), |acc, i| {
+            acc + (0..D)
+                .filter(|&j| j != i)
+                .fold(F::one(), |face, j| face * lengths[j])
+        });
+        faces + faces
+    }
+
+    /// Middle point of the cuboid
+    ///
+    /// For integer coordinates, the result is rounded towards zero.
+    pub fn center(&self) -> [F; D] {
+        let two = F::one() + F::one();
+        std::array::from_fn(|i| (self.min[i] + self.max[i]) / two)
+    }
+
+    /// Checks if the point lies inside or on the boundary of the cuboid
+    pub fn contains_point(&self, point: &[F; D]) -> bool {
+        (0..D).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Checks if the other cuboid lies completely inside of this cuboid
+    pub fn contains_cuboid(&self, other: &Self) -> bool {
+        (0..D).all(|i| self.min[i] <= other.min[i] && other.max[i] <= self.max[i])
+    }
+
+    /// Checks if the cuboids share at least one point
+    ///
+    /// Cuboids which only touch each other at their boundary do intersect.
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..D).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// Common part of both cuboids
+    ///
+    /// Returns [None] if the cuboids do not [intersect](Cuboid::intersects).
+    /// Cuboids which only touch each other produce an intersection of zero volume.
+    ///
+    /// ```
+    /// # use spatial_decomposition::*;
+    /// let c1 = Rectangle {
+    ///     min: [0.0; 2],
+    ///     max: [2.0; 2],
+    /// };
+    /// let c2 = Rectangle {
+    ///     min: [1.0; 2],
+    ///     max: [3.0, 1.5],
+    /// };
+    /// assert_eq!(
+    ///     c1.intersection(&c2),
+    ///     Some(Rectangle {
+    ///         min: [1.0; 2],
+    ///         max: [2.0, 1.5],
+    ///     })
+    /// );
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Cuboid {
+            min: std::array::from_fn(|i| max(self.min[i], other.min[i])),
+            max: std::array::from_fn(|i| min(self.max[i], other.max[i])),
+        })
+    }
+
+    /// Smallest cuboid which contains both cuboids
+    pub fn bounding_box(&self, other: &Self) -> Self {
+        Cuboid {
+            min: std::array::from_fn(|i| min(self.min[i], other.min[i])),
+            max: std::array::from_fn(|i| max(self.max[i], other.max[i])),
+        }
+    }
+
+    /// Splits the cuboid perpendicular to the given axis at the given coordinate.
+    ///
+    /// Returns [None] if the axis is out of bounds or if the coordinate does not lie strictly
+    /// between the bounds of the cuboid along this axis.
+    ///
+    /// ```
+    /// # use spatial_decomposition::*;
+    /// let c = Rectangle {
+    ///     min: [0; 2],
+    ///     max: [10, 4],
+    /// };
+    /// let (lower, upper) = c.split(0, 3).unwrap();
+    /// assert_eq!(lower.max, [3, 4]);
+    /// assert_eq!(upper.min, [3, 0]);
+    /// assert_eq!(c.split(1, 4), None);
+    /// ```
+    pub fn split(&self, axis: usize, at: F) -> Option<(Self, Self)> {
+        if axis >= D || at <= self.min[axis] || self.max[axis] <= at {
+            return None;
+        }
+        let mut lower = self.clone();
+        let mut upper = self.clone();
+        lower.max[axis] = at;
+        upper.min[axis] = at;
+        Some((lower, upper))
+    }
+
+    /// Enlarges the cuboid by the given margin on every side
+    pub fn expand(&self, margin: F) -> Self {
+        Cuboid {
+            min: self.min.map(|x| x - margin),
+            max: self.max.map(|x| x + margin),
+        }
+    }
+
+    /// Reduces the cuboid by the given margin on every side
+    ///
+    /// Returns [None] if the resulting cuboid would have negative side lengths.
+    pub fn shrink(&self, margin: F) -> Option<Self> {
+        let res = Cuboid {
+            min: self.min.map(|x| x + margin),
+            max: self.max.map(|x| x - margin),
+        };
+        (0..D).all(|i| res.min[i] <= res.max[i]).then_some(res)
+    }
+
+    /// Iterates over all `2^D` corners of the cuboid
+    ///
+    /// The `i`-th bit of the index of each corner determines if the corner lies at the lower
+    /// (unset) or upper (set) bound along axis `i`.
+    pub fn corners(&self) -> impl Iterator<Item = [F; D]> + '_ {
+        (0..1usize << D).map(move |n| {
+            std::array::from_fn(|i| {
+                if n & (1 << i) == 0 {
+                    self.min[i]
+                } else {
+                    self.max[i]
+                }
+            })
+        })
+    }
+}
+
+pub(crate) fn min<F: PartialOrd>(a: F, b: F) -> F {
+    if b < a { b } else { a }
+}
+
+pub(crate) fn max<F: PartialOrd>(a: F, b: F) -> F {
+    if b > a { b } else { a }
+}
+
+#[test]
+fn cuboid_surface_various_dimensions() {
+    let line = Cuboid {
+        min: [1.0],
+        max: [4.0],
+    };
+    assert_eq!(line.volume(), 3.0);
+    assert_eq!(line.surface(), 2.0);
+    let rect = Cuboid {
+        min: [1, 2],
+        max: [4, 7],
+    };
+    assert_eq!(rect.volume(), 15);
+    assert_eq!(rect.surface(), 16);
+    let hyper = Cuboid {
+        min: [0.0; 4],
+        max: [1.0, 2.0, 3.0, 4.0],
+    };
+    assert_eq!(hyper.volume(), 24.0);
+    assert_eq!(hyper.surface(), 2.0 * (24.0 + 12.0 + 8.0 + 6.0));
+}
+
+#[test]
+fn cuboid_corners() {
+    let c = Cuboid {
+        min: [0, 10, 20],
+        max: [1, 11, 21],
+    };
+    let corners: Vec<_> = c.corners().collect();
+    assert_eq!(
+        corners,
+        vec![
+            [0, 10, 20],
+            [1, 10, 20],
+            [0, 11, 20],
+            [1, 11, 20],
+            [0, 10, 21],
+            [1, 10, 21],
+            [0, 11, 21],
+            [1, 11, 21],
+        ]
+    );
+    assert!(corners.iter().all(|p| c.contains_point(p)));
+}
+
+#[test]
+fn cuboid_set_operations() {
+    let c1 = Cuboid {
+        min: [0.0; 3],
+        max: [2.0; 3],
+    };
+    let c2 = Cuboid {
+        min: [2.0, 0.0, 0.0],
+        max: [3.0; 3],
+    };
+    assert!(c1.intersects(&c2));
+    assert_eq!(c1.intersection(&c2).unwrap().volume(), 0.0);
+    assert_eq!(
+        c1.bounding_box(&c2),
+        Cuboid {
+            min: [0.0; 3],
+            max: [3.0; 3],
+        }
+    );
+    assert!(c1.bounding_box(&c2).contains_cuboid(&c1));
+    assert!(!c1.contains_cuboid(&c2));
+    let c3 = c2.expand(0.5);
+    assert_eq!(c3.min, [1.5, -0.5, -0.5]);
+    assert_eq!(c3.shrink(0.5), Some(c2.clone()));
+    assert_eq!(c2.shrink(0.6), None);
+    assert_eq!(
+        c1.intersection(&Cuboid {
+            min: [2.5; 3],
+            max: [3.0; 3],
+        }),
+        None
+    );
+}
@@ -0,0 +1,400 @@
+use num_traits::Num;
+use simba::scalar::RealField;
+
+use crate::{Error, KdTree, Rectangle, Result, SortedDigits, Subdomain};
+
+/// Assigns given digits to arbitrary subdomains.
+///
+/// Every digit is assigned to the first subdomain which contains its middle.
+/// Subdomains can be single [Rectangle]s as returned by [kmr_decompose](crate::kmr_decompose) or
+/// [CompositeDomain](crate::CompositeDomain)s as returned by
+/// [rectilinear_decompose](crate::rectilinear_decompose).
+/// All pieces of the subdomains are indexed by a [KdTree] such that this method is
+/// `O((n + m) log m)` where `n` is the number of digits and `m` the total number of pieces.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let obstacles = [Rectangle {
+///     min: [40.0; 2],
+///     max: [60.0; 2],
+/// }];
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let subdomains = decompose_with_holes(&domain, &obstacles, 3.try_into().unwrap())?;
+/// let digits = kmr_decompose(&domain, 100.try_into().unwrap())
+///     .into_iter()
+///     .enumerate()
+///     // Digits inside the obstacle can not be assigned
+///     .filter(|(_, digit)| !obstacles[0].contains_cuboid(digit));
+/// let sorted = digitize(subdomains, digits)?;
+/// assert_eq!(sorted.iter().map(|(_, digits)| digits.len()).sum::<usize>(), 96);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitize<F, S, I>(
+    subdomains: impl IntoIterator<Item = S>,
+    digits: impl IntoIterator<Item = (I, Rectangle<F>)>,
+) -> Result<SortedDigits<F, I, S>>
+where
+    F: Copy + RealField,
+    S: Subdomain<F, 2>,
+{
+    let mut res: SortedDigits<F, I, S> = subdomains
+        .into_iter()
+        .map(|subdomain| (subdomain, Vec::new()))
+        .collect();
+    let index = PieceIndex::new(res.iter().map(|(subdomain, _)| subdomain));
+    for (digit, rect) in digits.into_iter() {
+        let n = index.locate(&rect.center())?;
+        res[n].1.push((digit, rect));
+    }
+    Ok(res)
+}
+
+/// Assigns digits with integer coordinates to arbitrary subdomains.
+///
+/// Works like [digitize] but does not require a [RealField].
+/// The middle of every digit is compared exactly even if it does not lie on the integer grid,
+/// e.g. for pixels, voxels or tiles.
+/// No coordinate is multiplied or added such that the whole range of the integer type can be
+/// used.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0u32; 2],
+///     max: [64, 48],
+/// };
+/// let subdomains = kmr_decompose_integer(&domain, 5.try_into().unwrap());
+/// let pixels = (0..64).flat_map(|x| {
+///     (0..48).map(move |y| Rectangle {
+///         min: [x, y],
+///         max: [x + 1, y + 1],
+///     })
+/// });
+/// let sorted = digitize_integer(subdomains, pixels.enumerate())?;
+/// for (subdomain, pixels) in sorted.iter() {
+///     assert_eq!(subdomain.volume() as usize, pixels.len());
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitize_integer<F, S, I>(
+    subdomains: impl IntoIterator<Item = S>,
+    digits: impl IntoIterator<Item = (I, Rectangle<F>)>,
+) -> Result<SortedDigits<F, I, S>>
+where
+    F: Copy + PartialOrd + Num,
+    S: Subdomain<F, 2>,
+{
+    let mut res: SortedDigits<F, I, S> = subdomains
+        .into_iter()
+        .map(|subdomain| (subdomain, Vec::new()))
+        .collect();
+    let index = PieceIndex::new(res.iter().map(|(subdomain, _)| subdomain));
+    for (digit, rect) in digits.into_iter() {
+        let middle = [0, 1].map(|i| middle_interval(rect.min[i], rect.max[i]));
+        let n = index.locate_cuboid(&Rectangle {
+            min: middle.map(|(lower, _)| lower),
+            max: middle.map(|(_, upper)| upper),
+        })?;
+        res[n].1.push((digit, rect));
+    }
+    Ok(res)
+}
+
+/// Smallest interval with integer bounds which contains the middle of `[lower, upper]`
+///
+/// The interval consists of a single point if the middle is an integer.
+/// Otherwise, a cuboid contains the middle if and only if it contains the whole interval.
+fn middle_interval<F>(lower: F, upper: F) -> (F, F)
+where
+    F: Copy + PartialOrd + Num,
+{
+    // Halving before adding avoids overflows but rounds both halves towards zero
+    let two = F::one() + F::one();
+    let half = lower / two + upper / two;
+    let remainder = lower % two + upper % two;
+    if remainder == two {
+        (half + F::one(), half + F::one())
+    } else if remainder == F::one() {
+        (half, half + F::one())
+    } else if remainder == F::zero() {
+        (half, half)
+    } else if remainder + F::one() == F::zero() {
+        (half - F::one(), half)
+    } else {
+        (half - F::one(), half - F::one())
+    }
+}
+
+/// [KdTree] over all pieces of the given subdomains
+struct PieceIndex<F> {
+    tree: KdTree<F, 2>,
+    owners: Vec<usize>,
+}
+
+impl<F> PieceIndex<F>
+where
+    F: Copy + PartialOrd + Num,
+{
+    /// Indexes all pieces of the subdomains
+    fn new<'a, S>(subdomains: impl IntoIterator<Item = &'a S>) -> Self
+    where
+        F: 'a,
+        S: 'a + Subdomain<F, 2>,
+    {
+        let mut owners = Vec::new();
+        let tree = KdTree::new(
+            subdomains
+                .into_iter()
+                .enumerate()
+                .flat_map(|(n, subdomain)| {
+                    owners.extend(std::iter::repeat_n(n, subdomain.pieces().len()));
+                    subdomain.pieces().iter().cloned()
+                }),
+        );
+        PieceIndex { tree, owners }
+    }
+
+    /// Index of the subdomain which contains the point
+    fn locate(&self, point: &[F; 2]) -> Result<usize> {
+        let index = self.tree.locate(point).ok_or(Error::Indexing)?;
+        Ok(self.owners[index])
+    }
+
+    /// Index of the subdomain with the first piece which contains the whole cuboid
+    fn locate_cuboid(&self, cuboid: &Rectangle<F>) -> Result<usize> {
+        let index = self
+            .tree
+            .intersecting(cuboid)
+            .into_iter()
+            .find(|&n| self.tree.cuboids()[n].contains_cuboid(cuboid))
+            .ok_or(Error::Indexing)?;
+        Ok(self.owners[index])
+    }
+}
+
+/// Assigns points given as separate columns of coordinates to subdomains.
+///
+/// This is the struct-of-arrays variant of [digitize] which avoids constructing a [Rectangle]
+/// for every record, e.g. when digitizing large tables loaded from CSV files.
+/// Returns the index of the subdomain containing each point.
+/// Points on a shared boundary are assigned to the subdomain with the smallest index.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [90.0, 20.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 3.try_into().unwrap());
+///
+/// let xs = [5.0, 45.0, 85.0, 15.0];
+/// let ys = [1.0, 10.0, 19.0, 7.5];
+/// assert_eq!(digitize_points(&subdomains, &xs, &ys)?, vec![0, 1, 2, 0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitize_points<F, S>(subdomains: &[S], xs: &[F], ys: &[F]) -> Result<Vec<usize>>
+where
+    F: Copy + RealField,
+    S: Subdomain<F, 2>,
+{
+    if xs.len() != ys.len() {
+        return Err(Error::Columns);
+    }
+    let index = PieceIndex::new(subdomains);
+    xs.iter()
+        .zip(ys.iter())
+        .map(|(&x, &y)| index.locate(&[x, y]))
+        .collect()
+}
+
+/// Assigns digits given as separate columns of bounds to subdomains.
+///
+/// This is the struct-of-arrays variant of [digitize].
+/// Every digit is given by its lower bounds `(min_xs[i], min_ys[i])` and upper bounds
+/// `(max_xs[i], max_ys[i])` and assigned to the subdomain which contains its middle.
+/// Returns the index of the subdomain of each digit.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let subdomains = kmr_decompose(&domain, 4.try_into().unwrap());
+///
+/// let min_xs = [0.0, 60.0];
+/// let min_ys = [0.0, 60.0];
+/// let max_xs = [10.0, 70.0];
+/// let max_ys = [10.0, 70.0];
+/// let owners = digitize_columns(&subdomains, [&min_xs, &min_ys], [&max_xs, &max_ys])?;
+/// assert_eq!(owners, vec![0, 3]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitize_columns<F, S>(
+    subdomains: &[S],
+    min: [&[F]; 2],
+    max: [&[F]; 2],
+) -> Result<Vec<usize>>
+where
+    F: Copy + RealField,
+    S: Subdomain<F, 2>,
+{
+    let n = min[0].len();
+    if [min[1], max[0], max[1]].iter().any(|c| c.len() != n) {
+        return Err(Error::Columns);
+    }
+    let index = PieceIndex::new(subdomains);
+    let two = F::one() + F::one();
+    (0..n)
+        .map(|i| {
+            let middle = [0, 1].map(|j| (min[j][i] + max[j][i]) / two);
+            index.locate(&middle)
+        })
+        .collect()
+}
+
+/// Assigns an index to a given subspace.
+///
+/// Singular version of [digitize].
+pub fn digitize_single<F, S>(subdomains: &[S], subspace: &Rectangle<F>) -> Result<usize>
+where
+    F: Copy + RealField,
+    S: Subdomain<F, 2>,
+{
+    let middle = subspace.center();
+    subdomains
+        .iter()
+        .position(|subdomain| subdomain.pieces().iter().any(|p| p.contains_point(&middle)))
+        .ok_or(Error::Indexing)
+}
+
+#[test]
+fn digitize_composite_domains() {
+    let vertices = [
+        [0.0, 0.0],
+        [30.0, 0.0],
+        [30.0, 10.0],
+        [10.0, 10.0],
+        [10.0, 30.0],
+        [0.0, 30.0],
+    ];
+    let subdomains = crate::rectilinear_decompose(&vertices, 5.try_into().unwrap()).unwrap();
+    let digits: Vec<_> = (0..30)
+        .flat_map(|i| {
+            (0..30).map(move |j| Rectangle {
+                min: [i as f64, j as f64],
+                max: [i as f64 + 1.0, j as f64 + 1.0],
+            })
+        })
+        .filter(|d| d.min[0] < 10.0 || d.min[1] < 10.0)
+        .enumerate()
+        .collect();
+    let sorted = digitize(subdomains.clone(), digits.clone()).unwrap();
+    assert_eq!(sorted.len(), 5);
+    assert_eq!(
+        sorted.iter().map(|(_, digits)| digits.len()).sum::<usize>(),
+        digits.len()
+    );
+    for (n, (subdomain, digits)) in sorted.iter().enumerate() {
+        assert_eq!(subdomain, &subdomains[n]);
+        for (_, digit) in digits.iter() {
+            assert_eq!(digitize_single(&subdomains, digit).unwrap(), n);
+        }
+    }
+    let outside = Rectangle {
+        min: [20.0; 2],
+        max: [21.0; 2],
+    };
+    assert!(digitize(subdomains, [(0, outside)]).is_err());
+}
+
+#[test]
+fn digitize_columns_matches_digitize() {
+    let domain = Rectangle {
+        min: [-20.0, 10.0],
+        max: [80.0, 110.0],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 11.try_into().unwrap());
+    let digits = crate::kmr_decompose(&domain, 57.try_into().unwrap());
+    let sorted = digitize(subdomains.clone(), digits.iter().cloned().enumerate()).unwrap();
+    let mut expected = vec![0; digits.len()];
+    for (n, (_, digits)) in sorted.iter().enumerate() {
+        for (i, _) in digits.iter() {
+            expected[*i] = n;
+        }
+    }
+
+    let column = |f: fn(&Rectangle<f64>) -> f64| digits.iter().map(f).collect::<Vec<_>>();
+    let (min_xs, min_ys) = (column(|d| d.min[0]), column(|d| d.min[1]));
+    let (max_xs, max_ys) = (column(|d| d.max[0]), column(|d| d.max[1]));
+    let owners = digitize_columns(&subdomains, [&min_xs, &min_ys], [&max_xs, &max_ys]).unwrap();
+    assert_eq!(owners, expected);
+
+    let (xs, ys) = (column(|d| d.center()[0]), column(|d| d.center()[1]));
+    assert_eq!(digitize_points(&subdomains, &xs, &ys).unwrap(), expected);
+
+    assert!(matches!(
+        digitize_points(&subdomains, &xs, &ys[1..]),
+        Err(Error::Columns)
+    ));
+    assert!(matches!(
+        digitize_columns(&subdomains, [&min_xs, &min_ys], [&max_xs[1..], &max_ys]),
+        Err(Error::Columns)
+    ));
+    assert!(matches!(
+        digitize_points(&subdomains, &[-30.0], &[50.0]),
+        Err(Error::Indexing)
+    ));
+}
+
+#[test]
+fn digitize_integer_near_type_limits() {
+    assert_eq!(middle_interval(253u8, 255), (254, 254));
+    assert_eq!(middle_interval(254u8, 255), (254, 255));
+    assert_eq!(middle_interval(-3i8, 0), (-2, -1));
+    assert_eq!(middle_interval(-128i8, 127), (-1, 0));
+    assert_eq!(middle_interval(-128i8, -126), (-127, -127));
+    assert_eq!(
+        middle_interval(i64::MAX - 1, i64::MAX),
+        (i64::MAX - 1, i64::MAX)
+    );
+
+    // Doubled coordinates would exceed the range of u8 while the area still fits into it
+    let domain = Rectangle {
+        min: [240u8, 239],
+        max: [255, 255],
+    };
+    let subdomains = crate::kmr_decompose_integer(&domain, 5.try_into().unwrap());
+    let pixels = (240..255).flat_map(|x| {
+        (239..255).map(move |y| Rectangle {
+            min: [x, y],
+            max: [x + 1, y + 1],
+        })
+    });
+    let sorted = digitize_integer(subdomains, pixels.enumerate()).unwrap();
+    for (subdomain, pixels) in sorted.iter() {
+        let [dx, dy] = subdomain.lengths().map(usize::from);
+        assert_eq!(dx * dy, pixels.len());
+        for (_, pixel) in pixels.iter() {
+            assert!(subdomain.contains_cuboid(pixel));
+        }
+    }
+
+    let subdomains = [Rectangle {
+        min: [i64::MAX - 4, i64::MIN],
+        max: [i64::MAX, i64::MIN + 2],
+    }];
+    let digit = Rectangle {
+        min: [i64::MAX - 1, i64::MIN],
+        max: [i64::MAX, i64::MIN + 1],
+    };
+    let sorted = digitize_integer(subdomains, [(0, digit)]).unwrap();
+    assert_eq!(sorted[0].1.len(), 1);
+}
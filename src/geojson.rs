@@ -0,0 +1,387 @@
+use num_traits::{AsPrimitive, Num};
+use serde_json::{Value, json};
+
+use crate::{Error, Rectangle, Result, SortedDigits, Subdomain};
+
+fn invalid(message: &str) -> Error {
+    Error::GeoJson(message.to_string())
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid("coordinates need to be arrays"))
+}
+
+fn parse(geojson: &str) -> Result<Value> {
+    serde_json::from_str(geojson).map_err(|e| Error::GeoJson(e.to_string()))
+}
+
+/// Collects all geometries of a GeoJSON object in document order
+fn geometries(value: &Value) -> Result<Vec<&Value>> {
+    let kind = value["type"]
+        .as_str()
+        .ok_or_else(|| invalid("missing type"))?;
+    match kind {
+        "FeatureCollection" => {
+            let features = value["features"]
+                .as_array()
+                .ok_or_else(|| invalid("missing features"))?;
+            let mut res = Vec::new();
+            for feature in features {
+                res.extend(geometries(feature)?);
+            }
+            Ok(res)
+        }
+        "Feature" => match &value["geometry"] {
+            Value::Null => Ok(Vec::new()),
+            geometry => geometries(geometry),
+        },
+        "GeometryCollection" => {
+            let members = value["geometries"]
+                .as_array()
+                .ok_or_else(|| invalid("missing geometries"))?;
+            let mut res = Vec::new();
+            for member in members {
+                res.extend(geometries(member)?);
+            }
+            Ok(res)
+        }
+        "Point" | "MultiPoint" | "LineString" | "MultiLineString" | "Polygon" | "MultiPolygon" => {
+            Ok(vec![value])
+        }
+        _ => Err(Error::GeoJson(format!("unknown type {kind}"))),
+    }
+}
+
+/// Converts a position into a `2D` point and ignores the altitude
+fn position(value: &Value) -> Result<[f64; 2]> {
+    match value.as_array().map(|a| &a[..]) {
+        Some([x, y, ..]) => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => Ok([x, y]),
+            _ => Err(invalid("positions need to be numbers")),
+        },
+        _ => Err(invalid("positions need at least two coordinates")),
+    }
+}
+
+/// Collects all positions of nested coordinate arrays
+fn positions(value: &Value, res: &mut Vec<[f64; 2]>) -> Result<()> {
+    match value.as_array() {
+        Some(array) if array.first().is_some_and(|x| x.is_number()) => res.push(position(value)?),
+        Some(array) => {
+            for v in array {
+                positions(v, res)?;
+            }
+        }
+        None => return Err(invalid("coordinates need to be arrays")),
+    }
+    Ok(())
+}
+
+/// Reads the bounding box of a GeoJSON object.
+///
+/// The `bbox` member of the object is used if present.
+/// Bounding boxes which cross the antimeridian, i.e. whose western bound is larger than their
+/// eastern bound, can not be represented by a [Rectangle] and are rejected.
+/// Otherwise the bounding box of all positions of all geometries is calculated.
+/// Positions are interpreted as `[longitude, latitude]` and altitudes are ignored.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let region = r#"{
+///     "type": "Feature",
+///     "properties": {"name": "Island"},
+///     "geometry": {
+///         "type": "Polygon",
+///         "coordinates": [[[7.5, 47.5], [8.5, 47.0], [9.0, 48.5], [7.5, 47.5]]]
+///     }
+/// }"#;
+/// let domain = geojson_bounding_box(region)?;
+/// assert_eq!(domain, Rectangle {
+///     min: [7.5, 47.0],
+///     max: [9.0, 48.5],
+/// });
+///
+/// // Decompose the region and export the subdomains
+/// let subdomains = kmr_decompose(&domain, 4.try_into().unwrap());
+/// let collection = decomposition_geojson(&subdomains);
+/// assert_eq!(geojson_bounding_box(&collection)?, domain);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn geojson_bounding_box(geojson: &str) -> Result<Rectangle<f64>> {
+    let value = parse(geojson)?;
+    if let Some(bbox) = value["bbox"].as_array() {
+        let bbox = bbox
+            .iter()
+            .map(|x| {
+                x.as_f64()
+                    .ok_or_else(|| invalid("bbox needs to contain numbers"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return match bbox[..] {
+            [x0, y0, x1, y1] | [x0, y0, _, x1, y1, _] if x0 > x1 || y0 > y1 => Err(invalid(
+                "bbox needs lower bounds below upper bounds and must not cross the antimeridian",
+            )),
+            [x0, y0, x1, y1] | [x0, y0, _, x1, y1, _] => Ok(Rectangle {
+                min: [x0, y0],
+                max: [x1, y1],
+            }),
+            _ => Err(invalid("bbox needs four or six numbers")),
+        };
+    }
+    let mut points = Vec::new();
+    for geometry in geometries(&value)? {
+        positions(&geometry["coordinates"], &mut points)?;
+    }
+    let first = points.first().ok_or_else(|| invalid("no positions"))?;
+    Ok(points.iter().fold(
+        Rectangle {
+            min: *first,
+            max: *first,
+        },
+        |acc, p| acc.bounding_box(&Rectangle { min: *p, max: *p }),
+    ))
+}
+
+/// Reads the vertices of the only polygon of a GeoJSON object.
+///
+/// The closing vertex which repeats the first vertex is removed.
+/// Polygons with holes and objects with multiple polygons, including `MultiPolygon`s with more
+/// than one polygon, can not be represented by a single list of vertices and are rejected.
+/// Rectilinear polygons can be decomposed directly with
+/// [rectilinear_decompose](crate::rectilinear_decompose).
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let region = r#"{
+///     "type": "Polygon",
+///     "coordinates": [[[0, 0], [2, 0], [2, 1], [1, 1], [1, 2], [0, 2], [0, 0]]]
+/// }"#;
+/// let vertices = geojson_polygon(region)?;
+/// assert_eq!(vertices.len(), 6);
+/// let subdomains = rectilinear_decompose(&vertices, 3.try_into().unwrap())?;
+/// assert_eq!(subdomains.len(), 3);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn geojson_polygon(geojson: &str) -> Result<Vec<[f64; 2]>> {
+    let value = parse(geojson)?;
+    let mut polygons = Vec::new();
+    for geometry in geometries(&value)? {
+        match geometry["type"].as_str() {
+            Some("Polygon") => polygons.push(&geometry["coordinates"]),
+            Some("MultiPolygon") => polygons.extend(array(&geometry["coordinates"])?),
+            _ => (),
+        }
+    }
+    let polygon = match polygons[..] {
+        [polygon] => polygon,
+        [] => return Err(invalid("no polygon")),
+        _ => return Err(invalid("multiple polygons are not supported")),
+    };
+    let ring = match &array(polygon)?[..] {
+        [ring] => array(ring)?,
+        [] => return Err(invalid("polygon needs an exterior ring")),
+        _ => return Err(invalid("polygons with holes are not supported")),
+    };
+    let mut vertices = ring.iter().map(position).collect::<Result<Vec<_>>>()?;
+    if vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+    Ok(vertices)
+}
+
+/// Exterior ring of a rectangle in counterclockwise order
+fn ring<F>(rectangle: &Rectangle<F>) -> Value
+where
+    F: 'static + Copy + AsPrimitive<f64>,
+{
+    let [x0, y0]: [f64; 2] = rectangle.min.map(|x| x.as_());
+    let [x1, y1]: [f64; 2] = rectangle.max.map(|x| x.as_());
+    json!([[[x0, y0], [x1, y0], [x1, y1], [x0, y1], [x0, y0]]])
+}
+
+fn geometry<F, S>(subdomain: &S) -> Value
+where
+    F: 'static + Copy + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    match subdomain.pieces() {
+        [piece] => json!({"type": "Polygon", "coordinates": ring(piece)}),
+        pieces => json!({
+            "type": "MultiPolygon",
+            "coordinates": pieces.iter().map(ring).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn area<F, S>(subdomain: &S) -> f64
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    subdomain.pieces().iter().map(|p| p.volume().as_()).sum()
+}
+
+/// Writes the subdomains of a decomposition as GeoJSON `FeatureCollection`.
+///
+/// Every subdomain is stored as `Polygon` (or `MultiPolygon` if it consists of multiple pieces)
+/// with its index and area as properties `subdomain` and `area`.
+/// Coordinates are interpreted as `[longitude, latitude]`.
+/// Subdomains with multiple pieces can not be read back by [geojson_polygon].
+pub fn decomposition_geojson<F, S>(subdomains: &[S]) -> String
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    let features: Vec<_> = subdomains
+        .iter()
+        .enumerate()
+        .map(|(n, s)| {
+            json!({
+                "type": "Feature",
+                "properties": {"subdomain": n, "area": area(s)},
+                "geometry": geometry(s),
+            })
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features}).to_string()
+}
+
+/// Writes the subdomains of a digitization as GeoJSON `FeatureCollection`.
+///
+/// In addition to the properties written by [decomposition_geojson], every feature contains the
+/// number of digits assigned to its subdomain as property `digits`.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [-10.0, 35.0],
+///     max: [30.0, 60.0],
+/// };
+/// let digits = kmr_decompose(&domain, 50.try_into().unwrap());
+/// let sorted = kmr_digitize_1(&domain, 4.try_into().unwrap(), digits.into_iter().enumerate())?;
+/// let collection = digitization_geojson(&sorted);
+/// assert_eq!(collection.matches(r#""type":"Feature""#).count(), 4);
+/// assert!(collection.contains(r#""digits":"#));
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitization_geojson<F, I, S>(digits: &SortedDigits<F, I, S>) -> String
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    let features: Vec<_> = digits
+        .iter()
+        .enumerate()
+        .map(|(n, (s, digits))| {
+            json!({
+                "type": "Feature",
+                "properties": {"subdomain": n, "area": area(s), "digits": digits.len()},
+                "geometry": geometry(s),
+            })
+        })
+        .collect();
+    json!({"type": "FeatureCollection", "features": features}).to_string()
+}
+
+#[test]
+fn geojson_bounding_box_sources() {
+    let explicit = r#"{"type": "FeatureCollection", "bbox": [1, 2, 3, 4], "features": []}"#;
+    assert_eq!(
+        geojson_bounding_box(explicit).unwrap(),
+        Rectangle {
+            min: [1.0, 2.0],
+            max: [3.0, 4.0],
+        }
+    );
+    let collection = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": null, "geometry": {"type": "Point", "coordinates": [5, -1, 100]}},
+            {"type": "Feature", "properties": null, "geometry": null},
+            {"type": "Feature", "properties": null, "geometry": {
+                "type": "GeometryCollection",
+                "geometries": [{"type": "MultiLineString", "coordinates": [[[0, 0], [1, 3]], [[-2, 1], [0, 0]]]}]
+            }}
+        ]
+    }"#;
+    assert_eq!(
+        geojson_bounding_box(collection).unwrap(),
+        Rectangle {
+            min: [-2.0, -1.0],
+            max: [5.0, 3.0],
+        }
+    );
+    assert!(geojson_bounding_box(r#"{"type": "FeatureCollection", "features": []}"#).is_err());
+    let mixed = r#"{"type": "FeatureCollection", "bbox": [1, "a", 2, 3, 4], "features": []}"#;
+    assert!(geojson_bounding_box(mixed).is_err());
+    // Fiji crosses the antimeridian
+    let antimeridian =
+        r#"{"type": "FeatureCollection", "bbox": [177, -21, -178, -12], "features": []}"#;
+    assert!(geojson_bounding_box(antimeridian).is_err());
+    assert!(geojson_bounding_box(r#"{"type": "Circle"}"#).is_err());
+    assert!(geojson_bounding_box("not json").is_err());
+    assert!(geojson_polygon(r#"{"type": "Point", "coordinates": [0, 0]}"#).is_err());
+}
+
+#[test]
+fn geojson_roundtrip() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 5.try_into().unwrap());
+    let collection: Value = serde_json::from_str(&decomposition_geojson(&subdomains)).unwrap();
+    let features = collection["features"].as_array().unwrap();
+    assert_eq!(features.len(), 5);
+    for (n, feature) in features.iter().enumerate() {
+        assert_eq!(feature["properties"]["subdomain"], n);
+        assert_eq!(
+            feature["properties"]["area"].as_f64().unwrap(),
+            subdomains[n].volume()
+        );
+        let vertices = geojson_polygon(&feature.to_string()).unwrap();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(vertices[0], subdomains[n].min);
+        assert_eq!(vertices[2], subdomains[n].max);
+    }
+
+    // Composite subdomains are written as MultiPolygon which can not be read as a single polygon
+    let composite = crate::CompositeDomain::new(subdomains[..2].to_vec()).unwrap();
+    let collection = decomposition_geojson(&[composite]);
+    assert!(collection.contains("MultiPolygon"));
+    assert!(geojson_polygon(&collection).is_err());
+}
+
+#[test]
+fn geojson_polygon_rejects_lost_rings() {
+    let hole = r#"{
+        "type": "Polygon",
+        "coordinates": [
+            [[0, 0], [3, 0], [3, 3], [0, 3], [0, 0]],
+            [[1, 1], [1, 2], [2, 2], [2, 1], [1, 1]]
+        ]
+    }"#;
+    assert!(geojson_polygon(hole).is_err());
+    let multi = r#"{
+        "type": "MultiPolygon",
+        "coordinates": [
+            [[[0, 0], [1, 0], [1, 1], [0, 1], [0, 0]]],
+            [[[2, 0], [3, 0], [3, 1], [2, 1], [2, 0]]]
+        ]
+    }"#;
+    assert!(geojson_polygon(multi).is_err());
+    let single = r#"{
+        "type": "Feature",
+        "properties": null,
+        "geometry": {
+            "type": "MultiPolygon",
+            "coordinates": [[[[0, 0], [1, 0], [1, 1], [0, 1], [0, 0]]]]
+        }
+    }"#;
+    assert_eq!(geojson_polygon(single).unwrap().len(), 4);
+}
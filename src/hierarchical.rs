@@ -0,0 +1,215 @@
+use std::num::NonZeroUsize;
+
+use simba::scalar::RealField;
+
+use crate::{DecomposedDomain, Rectangle, kmr_decompose};
+
+/// Tree of subdomains generated by [decompose_hierarchical]
+///
+/// Every node stores its subdomain together with its multi-level index.
+/// The index of a node is the index of its parent extended by the position of the node among
+/// its siblings.
+/// The root has an empty index.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DomainTree<F> {
+    /// Subdomain covered by this node
+    pub rectangle: Rectangle<F>,
+    /// Multi-level index of this node
+    pub index: Vec<usize>,
+    /// Decomposition of this subdomain at the next level
+    pub children: Vec<DomainTree<F>>,
+}
+
+impl<F> DomainTree<F>
+where
+    F: Copy,
+{
+    /// Number of levels below this node
+    pub fn depth(&self) -> usize {
+        self.children
+            .iter()
+            .map(|c| c.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// All nodes without children in depth-first order
+    ///
+    /// Leaves are sorted lexicographically by their [index](DomainTree::index) such that leaves
+    /// belonging to the same parent are contiguous.
+    pub fn leaves(&self) -> Vec<&DomainTree<F>> {
+        if self.children.is_empty() {
+            return vec![self];
+        }
+        self.children.iter().flat_map(|c| c.leaves()).collect()
+    }
+
+    /// Subdomains of all [leaves](DomainTree::leaves) as a flat decomposition
+    pub fn flatten(&self) -> DecomposedDomain<F> {
+        self.leaves()
+            .into_iter()
+            .map(|l| l.rectangle.clone())
+            .collect()
+    }
+}
+
+/// Decomposes a rectangle recursively with [kmr_decompose].
+///
+/// The domain is first split into `levels[0]` subdomains, each of which is then split into
+/// `levels[1]` subdomains and so on.
+/// This matches the hierarchy of compute clusters where the domain is distributed among nodes
+/// first and the subdomain of every node among its cores afterwards.
+/// Since every level produces compact subdomains, the interfaces between subdomains of
+/// different nodes are kept short.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let tree = decompose_hierarchical(
+///     &domain,
+///     &[4.try_into().unwrap(), 6.try_into().unwrap()],
+/// );
+/// assert_eq!(tree.depth(), 2);
+/// assert_eq!(tree.children.len(), 4);
+///
+/// let leaves = tree.leaves();
+/// assert_eq!(leaves.len(), 24);
+/// assert_eq!(leaves[7].index, vec![1, 1]);
+/// assert!(tree.children[1].rectangle.contains_cuboid(&leaves[7].rectangle));
+/// ```
+pub fn decompose_hierarchical<F>(domain: &Rectangle<F>, levels: &[NonZeroUsize]) -> DomainTree<F>
+where
+    F: 'static + Copy + RealField,
+    F: num_traits::cast::AsPrimitive<usize>,
+    usize: num_traits::cast::AsPrimitive<F>,
+{
+    decompose_hierarchical_with(domain, levels, kmr_decompose)
+}
+
+/// Decomposes a rectangle recursively with the given strategy.
+///
+/// See [decompose_hierarchical].
+/// The strategy receives the subdomain of the current node and the number of subdomains of the
+/// next level.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [8.0, 4.0],
+/// };
+/// // Split into vertical strips of equal width
+/// let strips = |r: &Rectangle<f64>, n: std::num::NonZeroUsize| {
+///     let n = n.get();
+///     let width = (r.max[0] - r.min[0]) / n as f64;
+///     (0..n)
+///         .map(|i| Rectangle {
+///             min: [r.min[0] + i as f64 * width, r.min[1]],
+///             max: [r.min[0] + (i + 1) as f64 * width, r.max[1]],
+///         })
+///         .collect::<DecomposedDomain<f64>>()
+/// };
+/// let tree = decompose_hierarchical_with(
+///     &domain,
+///     &[2.try_into().unwrap(), 2.try_into().unwrap()],
+///     strips,
+/// );
+/// assert_eq!(tree.flatten()[1], Rectangle {
+///     min: [2.0, 0.0],
+///     max: [4.0, 4.0],
+/// });
+/// ```
+pub fn decompose_hierarchical_with<F, S>(
+    domain: &Rectangle<F>,
+    levels: &[NonZeroUsize],
+    mut strategy: S,
+) -> DomainTree<F>
+where
+    F: Copy,
+    S: FnMut(&Rectangle<F>, NonZeroUsize) -> DecomposedDomain<F>,
+{
+    fn build<F, S>(
+        rectangle: Rectangle<F>,
+        index: Vec<usize>,
+        levels: &[NonZeroUsize],
+        strategy: &mut S,
+    ) -> DomainTree<F>
+    where
+        F: Copy,
+        S: FnMut(&Rectangle<F>, NonZeroUsize) -> DecomposedDomain<F>,
+    {
+        let children = match levels.split_first() {
+            Some((&n, rest)) => strategy(&rectangle, n)
+                .into_iter()
+                .enumerate()
+                .map(|(i, child)| {
+                    let mut child_index = index.clone();
+                    child_index.push(i);
+                    build(child, child_index, rest, strategy)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        DomainTree {
+            rectangle,
+            index,
+            children,
+        }
+    }
+    build(domain.clone(), Vec::new(), levels, &mut strategy)
+}
+
+#[test]
+fn decompose_hierarchical_nested() {
+    use approx::assert_relative_eq;
+    let domain = Rectangle {
+        min: [-20.0, 10.0],
+        max: [80.0, 110.0],
+    };
+    let levels: Vec<NonZeroUsize> = [3, 4, 2].iter().map(|&n| n.try_into().unwrap()).collect();
+    let tree = decompose_hierarchical(&domain, &levels);
+    assert_eq!(tree.depth(), 3);
+    assert!(tree.index.is_empty());
+
+    let leaves = tree.leaves();
+    assert_eq!(leaves.len(), 24);
+    for (n, leaf) in leaves.iter().enumerate() {
+        assert_eq!(leaf.index, vec![n / 8, (n / 2) % 4, n % 2]);
+    }
+
+    // Every level covers the domain and children lie inside their parent
+    fn check(node: &DomainTree<f64>) {
+        let area: f64 = node.children.iter().map(|c| c.rectangle.volume()).sum();
+        if !node.children.is_empty() {
+            assert_relative_eq!(area, node.rectangle.volume(), max_relative = 1e-12);
+        }
+        for c in node.children.iter() {
+            assert!(node.rectangle.contains_cuboid(&c.rectangle));
+            check(c);
+        }
+    }
+    check(&tree);
+    let flat = tree.flatten();
+    assert_relative_eq!(
+        flat.iter().map(|r| r.volume()).sum::<f64>(),
+        domain.volume(),
+        max_relative = 1e-12
+    );
+}
+
+#[test]
+fn decompose_hierarchical_no_levels() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [1.0; 2],
+    };
+    let tree = decompose_hierarchical(&domain, &[]);
+    assert_eq!(tree.depth(), 0);
+    assert_eq!(tree.flatten(), vec![domain]);
+}
@@ -0,0 +1,218 @@
+use std::num::NonZeroUsize;
+
+use num_traits::{AsPrimitive, PrimInt};
+
+use crate::{Cuboid, DecomposedDomain, Rectangle, band_rectangles, kmr_bands};
+
+/// Boundaries of consecutive parts of `[min, max]` whose lengths are proportional to the given
+/// weights
+///
+/// The length of every part is rounded down and the remainder is distributed over the first parts
+/// such that the lengths of parts with equal weights differ by at most one and the last boundary
+/// equals `max` exactly.
+fn split<F>(min: F, max: F, weights: &[usize]) -> Vec<F>
+where
+    F: PrimInt,
+{
+    // Computed in a wider type such that neither the length nor any product can overflow
+    let lower = min.to_i128().unwrap();
+    let length = (max.to_i128().unwrap() - lower) as u128;
+    let total = weights.iter().sum::<usize>() as u128;
+    let (q, r) = (length / total, length % total);
+    let mut parts: Vec<u128> = weights
+        .iter()
+        .map(|&w| q * w as u128 + r * w as u128 / total)
+        .collect();
+    let remainder = (length - parts.iter().sum::<u128>()) as usize;
+    for part in parts.iter_mut().take(remainder) {
+        *part += 1;
+    }
+    let mut boundary = lower;
+    let mut res = vec![min];
+    for part in parts {
+        boundary += part as i128;
+        res.push(F::from(boundary).unwrap());
+    }
+    res
+}
+
+/// Partitions a rectangle with integer coordinates such as an image or a map of tiles.
+///
+/// The subdomains have the same band structure as the ones generated by
+/// [kmr_decompose](crate::kmr_decompose).
+/// Instead of dividing the side lengths of bands and subdomains exactly, the remainders are
+/// distributed over the first bands and the first subdomains of every band.
+/// Thus the side lengths of subdomains within a band differ by at most one and all subdomains
+/// cover the rectangle exactly without gaps.
+/// The subdomains are ordered band by band.
+/// If the rectangle is smaller than the number of subdomains along one axis, some subdomains
+/// are empty.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let image = Rectangle {
+///     min: [0u32; 2],
+///     max: [1920, 1080],
+/// };
+/// let subdomains = kmr_decompose_integer(&image, 7.try_into().unwrap());
+/// assert_eq!(subdomains.len(), 7);
+/// assert_eq!(
+///     subdomains.iter().map(|s| s.volume()).sum::<u32>(),
+///     1920 * 1080
+/// );
+/// // Two rows of 4 and 3 subdomains where the remaining pixel row of
+/// // 1080 = 617 + 462 + 1 is added to the first row
+/// assert_eq!(subdomains[0].lengths(), [480, 618]);
+/// assert_eq!(subdomains[6].lengths(), [640, 462]);
+/// ```
+pub fn kmr_decompose_integer<F>(
+    rectangle: &Rectangle<F>,
+    n_subdomains: NonZeroUsize,
+) -> DecomposedDomain<F>
+where
+    F: 'static + PrimInt + AsPrimitive<f64>,
+    usize: AsPrimitive<F>,
+{
+    let lengths: [f64; 2] = rectangle.lengths().map(|l| l.as_());
+    let bands = kmr_bands::<f64>(lengths, n_subdomains.get());
+    band_rectangles(rectangle, &bands, split)
+}
+
+/// Partitions a cuboid with integer coordinates into a regular grid.
+///
+/// This is intended for voxel domains in any dimension.
+/// Along every axis, the cuboid is divided into the given number of parts whose lengths differ by
+/// at most one.
+/// The cuboids are ordered such that the index along the first axis changes fastest.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let voxels = Cuboid {
+///     min: [0i64; 3],
+///     max: [10, 7, 5],
+/// };
+/// let counts = [3, 2, 2].map(|n| n.try_into().unwrap());
+/// let subdomains = grid_decompose_integer(&voxels, counts);
+/// assert_eq!(subdomains.len(), 12);
+/// assert_eq!(subdomains[0].lengths(), [4, 4, 3]);
+/// assert_eq!(subdomains[11].lengths(), [3, 3, 2]);
+/// ```
+pub fn grid_decompose_integer<F, const D: usize>(
+    cuboid: &Cuboid<F, D>,
+    counts: [NonZeroUsize; D],
+) -> Vec<Cuboid<F, D>>
+where
+    F: 'static + PrimInt,
+    usize: AsPrimitive<F>,
+{
+    let boundaries: [Vec<F>; D] =
+        std::array::from_fn(|i| split(cuboid.min[i], cuboid.max[i], &vec![1; counts[i].get()]));
+    let n_total = counts.iter().map(|n| n.get()).product();
+    (0..n_total)
+        .map(|n| {
+            let mut index = n;
+            let mut min = cuboid.min;
+            let mut max = cuboid.max;
+            for i in 0..D {
+                let j = index % counts[i].get();
+                index /= counts[i].get();
+                (min[i], max[i]) = (boundaries[i][j], boundaries[i][j + 1]);
+            }
+            Cuboid { min, max }
+        })
+        .collect()
+}
+
+#[test]
+fn split_distributes_remainder() {
+    assert_eq!(split(-3, 7, &[1; 4]), vec![-3, 0, 3, 5, 7]);
+    assert_eq!(split(0u8, 2, &[1; 3]), vec![0, 1, 2, 2]);
+    assert_eq!(split(5i64, 5, &[1]), vec![5, 5]);
+    assert_eq!(split(0, 10, &[3, 2]), vec![0, 6, 10]);
+    assert_eq!(split(0, 7, &[2, 1]), vec![0, 5, 7]);
+}
+
+#[test]
+fn kmr_decompose_integer_covers_exactly() {
+    let domain = Rectangle {
+        min: [-7i32, 3],
+        max: [93, 73],
+    };
+    for p in 1..=60 {
+        let subdomains = kmr_decompose_integer(&domain, p.try_into().unwrap());
+        assert_eq!(subdomains.len(), p);
+        assert_eq!(subdomains.iter().map(|s| s.volume()).sum::<i32>(), 7000);
+        assert!(crate::verify_partition(&domain, &subdomains, 0).is_empty());
+        let rectangles: Vec<_> = subdomains
+            .iter()
+            .map(|s| Rectangle {
+                min: s.min.map(|x| x as f64),
+                max: s.max.map(|x| x as f64),
+            })
+            .collect();
+        let layout = crate::BandLayout::from_rectangles(&rectangles).unwrap();
+        for band in layout.cuts() {
+            let widths: Vec<_> = band.windows(2).map(|c| c[1] - c[0]).collect();
+            let min = widths.iter().cloned().fold(f64::MAX, f64::min);
+            let max = widths.iter().cloned().fold(f64::MIN, f64::max);
+            assert!(max - min <= 1.0);
+        }
+    }
+}
+
+#[test]
+fn kmr_decompose_integer_matches_float() {
+    let domain = Rectangle {
+        min: [0u64; 2],
+        max: [60, 36],
+    };
+    let subdomains = kmr_decompose_integer(&domain, 7.try_into().unwrap());
+    let float_domain = Rectangle {
+        min: [0.0; 2],
+        max: [60.0, 36.0],
+    };
+    let float_subdomains = crate::kmr_decompose(&float_domain, 7.try_into().unwrap());
+    // Rows of 36 * 4 / 7 and 36 * 3 / 7 are rounded to whole units
+    for (s, f) in subdomains.iter().zip(float_subdomains.iter()) {
+        let s = Rectangle {
+            min: s.min.map(|x| x as f64),
+            max: s.max.map(|x| x as f64),
+        };
+        approx::assert_abs_diff_eq!(s, f, epsilon = 1.0);
+    }
+}
+
+#[test]
+fn integer_decomposition_narrow_types() {
+    // 254 = 17 * 14 + 16 where 16 * 17 exceeds the range of u8
+    let boundaries = split(0u8, 254, &[1; 17]);
+    assert_eq!(boundaries[16], 240);
+    assert_eq!(boundaries[17], 254);
+    assert_eq!(split(-128i8, 127, &[1; 3]), vec![-128, -43, 42, 127]);
+    assert_eq!(split(0u8, 255, &[200, 55]), vec![0, 200, 255]);
+
+    let domain = Rectangle {
+        min: [0u8; 2],
+        max: [254, 1],
+    };
+    let subdomains = kmr_decompose_integer(&domain, 17.try_into().unwrap());
+    assert_eq!(subdomains.len(), 17);
+    assert!(crate::verify_partition(&domain, &subdomains, 0).is_empty());
+
+    let counts = [17, 1].map(|n| n.try_into().unwrap());
+    let subdomains = grid_decompose_integer(&domain, counts);
+    assert!(crate::verify_partition(&domain, &subdomains, 0).is_empty());
+    for s in subdomains {
+        assert!(s.lengths()[0] == 14 || s.lengths()[0] == 15);
+    }
+
+    let line = crate::Line {
+        min: [i8::MIN],
+        max: [-1],
+    };
+    let subdomains = grid_decompose_integer(&line, [5.try_into().unwrap()]);
+    let lengths: Vec<_> = subdomains.iter().map(|s| s.lengths()[0]).collect();
+    assert_eq!(lengths, vec![26, 26, 25, 25, 25]);
+}
@@ -0,0 +1,356 @@
+use num_traits::Num;
+
+use crate::Cuboid;
+
+/// Maximum number of cuboids stored in a leaf of the [KdTree]
+const LEAF_SIZE: usize = 8;
+
+#[derive(Clone, Debug)]
+struct Node<F, const D: usize> {
+    bounds: Cuboid<F, D>,
+    range: std::ops::Range<usize>,
+    children: Option<(usize, usize)>,
+}
+
+/// Spatial index over a collection of [Cuboid]s
+///
+/// The tree recursively splits the given cuboids at the median of their centers along the axis
+/// of largest spread and stores the bounding box of every node.
+/// This allows to answer point location, box intersection and nearest-neighbour queries in
+/// `O(log p)` for well-behaved decompositions with `p` subdomains.
+/// Since a [DecomposedDomain](crate::DecomposedDomain) is simply a list of rectangles, any
+/// decomposition can be indexed.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let subdomains = kmr_decompose(&domain, 9.try_into().unwrap());
+/// let tree = KdTree::new(subdomains.clone());
+///
+/// let index = tree.locate(&[50.0; 2]).unwrap();
+/// assert_eq!(subdomains[index], Rectangle {
+///     min: [100.0 / 3.0; 2],
+///     max: [200.0 / 3.0; 2],
+/// });
+///
+/// // The four corner subdomains touch the center subdomain only at a single point
+/// let neighbours = tree.intersecting(&subdomains[index]);
+/// assert_eq!(neighbours.len(), 9);
+/// ```
+#[derive(Clone, Debug)]
+pub struct KdTree<F, const D: usize> {
+    cuboids: Vec<Cuboid<F, D>>,
+    order: Vec<usize>,
+    nodes: Vec<Node<F, D>>,
+}
+
+fn distance_squared<F, const D: usize>(cuboid: &Cuboid<F, D>, point: &[F; D]) -> F
+where
+    F: Copy + PartialOrd + Num,
+{
+    let mut dist = F::zero();
+    for (i, &x) in point.iter().enumerate() {
+        let d = if x < cuboid.min[i] {
+            cuboid.min[i] - x
+        } else if x > cuboid.max[i] {
+            x - cuboid.max[i]
+        } else {
+            F::zero()
+        };
+        dist = dist + d * d;
+    }
+    dist
+}
+
+impl<F, const D: usize> KdTree<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    /// Builds the tree from the given cuboids.
+    ///
+    /// All indices returned by queries refer to the order in which the cuboids were supplied.
+    pub fn new(cuboids: impl IntoIterator<Item = Cuboid<F, D>>) -> Self {
+        let cuboids: Vec<_> = cuboids.into_iter().collect();
+        let mut tree = KdTree {
+            order: (0..cuboids.len()).collect(),
+            cuboids,
+            nodes: Vec::new(),
+        };
+        if !tree.cuboids.is_empty() {
+            tree.build(0..tree.cuboids.len());
+        }
+        tree
+    }
+
+    fn build(&mut self, range: std::ops::Range<usize>) -> usize {
+        let bounds = self.order[range.clone()]
+            .iter()
+            .skip(1)
+            .fold(self.cuboids[self.order[range.start]].clone(), |acc, &n| {
+                acc.bounding_box(&self.cuboids[n])
+            });
+        let node_index = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            range: range.clone(),
+            children: None,
+        });
+        if range.len() <= LEAF_SIZE {
+            return node_index;
+        }
+
+        // Halving both bounds before adding them avoids overflows for integer coordinates close
+        // to the limits of their type.
+        // The rounding of integer centers does not matter since they are only compared.
+        let two = F::one() + F::one();
+        let center2 = |c: &Cuboid<F, D>, i: usize| c.min[i] / two + c.max[i] / two;
+        let mut axis = 0;
+        let mut max_spread = None;
+        for i in 0..D {
+            let mut values = self.order[range.clone()]
+                .iter()
+                .map(|&n| center2(&self.cuboids[n], i));
+            let first = values.next().unwrap();
+            let (lower, upper) = values.fold((first, first), |(lower, upper), v| {
+                (
+                    if v < lower { v } else { lower },
+                    if v > upper { v } else { upper },
+                )
+            });
+            let spread = upper / two - lower / two;
+            if max_spread.is_none_or(|s| spread > s) {
+                max_spread = Some(spread);
+                axis = i;
+            }
+        }
+
+        let mid = range.len() / 2;
+        let cuboids = &self.cuboids;
+        self.order[range.clone()].select_nth_unstable_by(mid, |&n, &m| {
+            center2(&cuboids[n], axis)
+                .partial_cmp(&center2(&cuboids[m], axis))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let left = self.build(range.start..range.start + mid);
+        let right = self.build(range.start + mid..range.end);
+        self.nodes[node_index].children = Some((left, right));
+        node_index
+    }
+
+    /// Number of cuboids stored in the tree
+    pub fn len(&self) -> usize {
+        self.cuboids.len()
+    }
+
+    /// Returns `true` if the tree does not contain any cuboids
+    pub fn is_empty(&self) -> bool {
+        self.cuboids.is_empty()
+    }
+
+    /// All cuboids in the order in which they were supplied
+    pub fn cuboids(&self) -> &[Cuboid<F, D>] {
+        &self.cuboids
+    }
+
+    /// Returns the smallest index of all cuboids which contain the given point.
+    ///
+    /// Bounds are inclusive such that points on shared edges are assigned to the subdomain which
+    /// comes first.
+    /// This matches the behaviour of [kmr_digitize_1_single](crate::kmr_digitize_1_single).
+    pub fn locate(&self, point: &[F; D]) -> Option<usize> {
+        let mut result = None;
+        self.visit(
+            |bounds| bounds.contains_point(point),
+            |n, cuboid| {
+                if cuboid.contains_point(point) && result.is_none_or(|r| n < r) {
+                    result = Some(n);
+                }
+            },
+        );
+        result
+    }
+
+    /// Returns the sorted indices of all cuboids which intersect the given cuboid.
+    ///
+    /// Cuboids which only touch the query at their boundary are also included.
+    pub fn intersecting(&self, cuboid: &Cuboid<F, D>) -> Vec<usize> {
+        let mut result = Vec::new();
+        self.visit(
+            |bounds| bounds.intersects(cuboid),
+            |n, c| {
+                if c.intersects(cuboid) {
+                    result.push(n);
+                }
+            },
+        );
+        result.sort_unstable();
+        result
+    }
+
+    /// Returns the indices of the `k` cuboids closest to the given point.
+    ///
+    /// Distances are measured from the point to the closest point of each cuboid such that all
+    /// cuboids containing the point have distance zero.
+    /// The result is sorted by distance and ties are broken by index.
+    pub fn nearest(&self, point: &[F; D], k: usize) -> Vec<usize> {
+        let mut best: Vec<(F, usize)> = Vec::with_capacity(k + 1);
+        if k == 0 || self.nodes.is_empty() {
+            return Vec::new();
+        }
+        let is_better =
+            |(d1, n1): (F, usize), (d2, n2): (F, usize)| d1 < d2 || (d1 == d2 && n1 < n2);
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let bound = distance_squared(&node.bounds, point);
+            if best.len() == k && best[k - 1].0 < bound {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    // Visit the closer child first to tighten the bound early
+                    let dl = distance_squared(&self.nodes[left].bounds, point);
+                    let dr = distance_squared(&self.nodes[right].bounds, point);
+                    if dl < dr {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                }
+                None => {
+                    for &n in self.order[node.range.clone()].iter() {
+                        let candidate = (distance_squared(&self.cuboids[n], point), n);
+                        if best.len() == k && !is_better(candidate, best[k - 1]) {
+                            continue;
+                        }
+                        let pos = best
+                            .iter()
+                            .position(|&b| is_better(candidate, b))
+                            .unwrap_or(best.len());
+                        best.insert(pos, candidate);
+                        best.truncate(k);
+                    }
+                }
+            }
+        }
+        best.into_iter().map(|(_, n)| n).collect()
+    }
+
+    fn visit(
+        &self,
+        enter: impl Fn(&Cuboid<F, D>) -> bool,
+        mut leaf: impl FnMut(usize, &Cuboid<F, D>),
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([left, right]),
+                None => {
+                    for &n in self.order[node.range.clone()].iter() {
+                        leaf(n, &self.cuboids[n]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn pseudo_random_cuboids(n: usize) -> Vec<Cuboid<f64, 2>> {
+    // Simple linear congruential generator to obtain reproducible positions
+    let mut state = 12345u64;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    (0..n)
+        .map(|_| {
+            let min = [100.0 * next(), 100.0 * next()];
+            let max = [min[0] + 10.0 * next(), min[1] + 10.0 * next()];
+            Cuboid { min, max }
+        })
+        .collect()
+}
+
+#[test]
+fn kd_tree_locate_matches_linear_search() {
+    let domain = crate::Rectangle {
+        min: [-3.0, 2.0],
+        max: [57.0, 33.0],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 47.try_into().unwrap());
+    let tree = KdTree::new(subdomains.clone());
+    for digit in crate::kmr_decompose(&domain, 997.try_into().unwrap()) {
+        let index = crate::kmr_digitize_1_single(&subdomains, &digit).unwrap();
+        let middle = [
+            (digit.min[0] + digit.max[0]) / 2.0,
+            (digit.min[1] + digit.max[1]) / 2.0,
+        ];
+        assert_eq!(tree.locate(&middle), Some(index));
+    }
+    assert_eq!(tree.locate(&[-3.1, 10.0]), None);
+}
+
+#[test]
+fn kd_tree_intersecting_matches_linear_search() {
+    let cuboids = pseudo_random_cuboids(300);
+    let tree = KdTree::new(cuboids.clone());
+    for query in pseudo_random_cuboids(50) {
+        let expected: Vec<_> = (0..cuboids.len())
+            .filter(|&n| cuboids[n].intersects(&query))
+            .collect();
+        assert_eq!(tree.intersecting(&query), expected);
+    }
+}
+
+#[test]
+fn kd_tree_nearest_matches_linear_search() {
+    let cuboids = pseudo_random_cuboids(300);
+    let tree = KdTree::new(cuboids.clone());
+    for query in pseudo_random_cuboids(20) {
+        let point = query.min;
+        let mut expected: Vec<_> = (0..cuboids.len())
+            .map(|n| (distance_squared(&cuboids[n], &point), n))
+            .collect();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let expected: Vec<_> = expected.into_iter().take(7).map(|(_, n)| n).collect();
+        assert_eq!(tree.nearest(&point, 7), expected);
+    }
+    assert!(tree.nearest(&[0.0; 2], 0).is_empty());
+    assert_eq!(tree.nearest(&[0.0; 2], 1000).len(), 300);
+}
+
+#[test]
+fn kd_tree_integer_coordinates() {
+    let cuboids: Vec<Cuboid<i64, 2>> = (0..20)
+        .flat_map(|i| {
+            (0..20).map(move |j| Cuboid {
+                min: [i * 5, j * 5],
+                max: [(i + 1) * 5, (j + 1) * 5],
+            })
+        })
+        .collect();
+    let tree = KdTree::new(cuboids);
+    assert_eq!(tree.locate(&[1, 1]), Some(0));
+    assert_eq!(tree.locate(&[12, 7]), Some(41));
+    assert_eq!(
+        tree.intersecting(&Cuboid {
+            min: [6; 2],
+            max: [9; 2]
+        }),
+        vec![21]
+    );
+}
@@ -1,216 +1,231 @@
 use std::num::NonZeroUsize;
 
-use approx::RelativeEq;
-use num_traits::AsPrimitive;
+use num_traits::{AsPrimitive, Num};
 use simba::scalar::RealField;
 
+#[cfg(feature = "rational")]
+use num_rational::Ratio;
+
 use crate::Rectangle;
+use crate::cuboid::max;
 
-#[allow(non_camel_case_types)]
-enum Decomposition<F> {
-    row(F),
-    col(F),
+/// Conversions of the band search which are not provided by [Num]
+///
+/// [Floats] rounds with the methods of a [RealField] while [Rationals] computes all values
+/// exactly.
+trait Arithmetic<F> {
+    fn from_usize(n: usize) -> F;
 }
 
-#[allow(non_snake_case)]
-#[derive(Clone, Debug, RelativeEq, PartialEq)]
-struct KongMountRoscoeValues<F> {
-    p: F,
-    h1: F,
-    h2: F,
-    k1: F,
-    k2: F,
-    A: F,
-    B: F,
-    C: F,
-    S: F,
-}
-
-impl<F> KongMountRoscoeValues<F>
+struct Floats;
+
+impl<F> Arithmetic<F> for Floats
 where
     F: 'static + Copy + RealField,
     usize: num_traits::cast::AsPrimitive<F>,
 {
-    #[allow(non_snake_case)]
-    fn calculate(A: F, B: F, n_subdomains: usize) -> Self {
-        let p: F = n_subdomains.as_();
-
-        let h1 = (A * p / B).sqrt().floor();
-        let h2 = (A * p / B).sqrt().ceil();
-        let k1 = (B * p / A).sqrt().floor();
-        let k2 = (B * p / A).sqrt().ceil();
-
-        let mut C = p;
-        let mut S = F::zero();
-        for k in 1..n_subdomains {
-            for h in 1..n_subdomains {
-                let k = k.as_();
-                let h = h.as_();
-                if (h - F::one()) * (k - F::one()) < p {
-                    let ci = (A / h).max(B / k);
-                    C = C.min(ci);
-                }
-                if p < (h + F::one()) * (k + F::one()) {
-                    let si = (A / h).min(B / k);
-                    S = S.max(si);
-                }
+    fn from_usize(n: usize) -> F {
+        n.as_()
+    }
+}
+
+#[cfg(feature = "rational")]
+struct Rationals;
+
+#[cfg(feature = "rational")]
+impl<T> Arithmetic<Ratio<T>> for Rationals
+where
+    T: Copy + num_integer::Integer + num_traits::FromPrimitive,
+{
+    fn from_usize(n: usize) -> Ratio<T> {
+        Ratio::from_integer(T::from_usize(n).unwrap())
+    }
+}
+
+/// Distributes `p` subdomains over `n_bands` bands such that the first bands contain one
+/// subdomain more than the others
+fn band_counts(axis: usize, n_bands: usize, p: usize) -> (usize, Vec<usize>) {
+    let (n, r) = (p / n_bands, p % n_bands);
+    let counts = (0..n_bands)
+        .map(|i| if i < r { n + 1 } else { n })
+        .collect();
+    (axis, counts)
+}
+
+/// Largest half perimeter of all subdomains of the given band structure
+///
+/// Every band is as thick as required such that all subdomains have the same area.
+fn half_perimeter<F, M>(lengths: [F; 2], (axis, counts): &(usize, Vec<usize>), p: usize) -> F
+where
+    F: Copy + PartialOrd + Num,
+    M: Arithmetic<F>,
+{
+    let (thickness, width) = (lengths[*axis], lengths[1 - axis]);
+    // Bands contain at most two different numbers of subdomains
+    [counts[0], counts[counts.len() - 1]]
+        .into_iter()
+        .map(|n| {
+            let n = M::from_usize(n);
+            thickness * n / M::from_usize(p) + width / n
+        })
+        .fold(F::zero(), max)
+}
+
+/// Total length of all interfaces between subdomains of the given band structure
+fn interface_length<F, M>(lengths: [F; 2], (axis, counts): &(usize, Vec<usize>), p: usize) -> F
+where
+    F: Copy + PartialOrd + Num,
+    M: Arithmetic<F>,
+{
+    let (thickness, width) = (lengths[*axis], lengths[1 - axis]);
+    // Boundaries between bands span the whole width while the n - 1 cuts within a band span
+    // its thickness
+    let between = M::from_usize(counts.len() - 1) * width;
+    counts.iter().fold(between, |total, &n| {
+        total + thickness * M::from_usize(n * (n - 1)) / M::from_usize(p)
+    })
+}
+
+/// Largest ratio of the longer to the shorter side of all subdomains of the given band structure
+fn aspect_ratio<F, M>(lengths: [F; 2], (axis, counts): &(usize, Vec<usize>), p: usize) -> F
+where
+    F: Copy + PartialOrd + Num,
+    M: Arithmetic<F>,
+{
+    let (thickness, width) = (lengths[*axis], lengths[1 - axis]);
+    [counts[0], counts[counts.len() - 1]]
+        .into_iter()
+        .map(|n| {
+            let n = M::from_usize(n);
+            let (t, w) = (thickness * n / M::from_usize(p), width / n);
+            max(t / w, w / t)
+        })
+        .fold(F::zero(), max)
+}
+
+/// Band structure with the smallest cost among all structures with any number of bands along
+/// any axis
+///
+/// Ties are resolved in favour of rows and fewer bands.
+fn best_bands<F: PartialOrd>(
+    p: usize,
+    cost: impl Fn(&(usize, Vec<usize>)) -> F,
+) -> (usize, Vec<usize>) {
+    let mut best = band_counts(1, 1, p);
+    let mut best_cost = cost(&best);
+    for axis in [1, 0] {
+        for n_bands in 1..=p {
+            let candidate = band_counts(axis, n_bands, p);
+            let candidate_cost = cost(&candidate);
+            if candidate_cost < best_cost {
+                (best, best_cost) = (candidate, candidate_cost);
             }
         }
+    }
+    best
+}
 
-        #[rustfmt::skip]
-        return KongMountRoscoeValues {
-            p,
-            h1, h2, k1, k2,
-            A,  B,  C,  S,
+/// Band structure of the decomposition of a rectangle with the given side lengths
+///
+/// All band structures with any number of bands along either axis are searched for the smallest
+/// maximum perimeter.
+#[allow(non_snake_case)]
+fn layout<F, M>(lengths: [F; 2], n_subdomains: usize) -> (usize, Vec<usize>)
+where
+    F: Copy + PartialOrd + Num,
+    M: Arithmetic<F>,
+{
+    let [B, A] = lengths;
+    let p = M::from_usize(n_subdomains);
+    let very_wide = A.is_zero() || B.is_zero() || p <= max(B / A, A / B);
+    if n_subdomains == 1 || very_wide {
+        return if B >= A {
+            (1, vec![n_subdomains])
+        } else {
+            (0, vec![n_subdomains])
         };
     }
+    best_bands(n_subdomains, |bands| {
+        half_perimeter::<F, M>(lengths, bands, n_subdomains)
+    })
 }
 
-impl<F> Decomposition<F> {
-    #[rustfmt::skip]
-    fn figure_out(values: &KongMountRoscoeValues<F>) -> Option<Decomposition<F>>
-    where
-        F: RealField + Copy,
-    {
-        use Decomposition::*;
-        let KongMountRoscoeValues {
-            p,
-            h1, h2, k1, k2,
-            A,  B,  C,  S,
-        } = values.clone();
-        let h0 = (p / k2).floor();
-        let h3 = (p / k1).ceil();
-        let k0 = (p / h2).floor();
-        let k3 = (p / h1).ceil();
-
-        if h1==h2 && k1==k2 {return Some(col(h1));}
-
-        //  Lemma 2.9
-        if h0 < h1 && B / k0 <= S {return Some(row(h2));}
-        if k3 > k2 && A / h0 >= C {return Some(row(h1));}
-        if h0 < h1 && A / h0 <= S {return Some(col(k2));}
-        if h3 > h2 && A / h3 >= C {return Some(col(k1));}
-
-        // Lemma 2.10
-        if h0 == h1 && A / h1 <= S && A / h2 >= C {return Some(col(k2));}
-        if h3 == h2 && A / h1 <= S && A / h2 >= C {return Some(col(k1));}
-        if k0 == k1 && B / k1 <= S && B / k2 >= C {return Some(row(h2));}
-        if k3 == k2 && B / k1 <= S && B / k2 >= C {return Some(row(h1));}
-
-        // TODO is this exhaustive?
-        // -> probably not!
-
-        None
+/// Band structure of [kmr_decompose] for a rectangle with the given side lengths
+///
+/// Returns the axis along which the bands are stacked and the number of subdomains in every band.
+/// The thickness of every band is proportional to its number of subdomains and all subdomains
+/// within a band are equally wide.
+pub(crate) fn kmr_bands<F>(lengths: [F; 2], n_subdomains: usize) -> (usize, Vec<usize>)
+where
+    F: 'static + Copy + RealField,
+    F: num_traits::cast::AsPrimitive<usize>,
+    usize: num_traits::cast::AsPrimitive<F>,
+{
+    layout::<F, Floats>(lengths, n_subdomains)
+}
+
+/// Boundaries of consecutive parts of `[lower, upper]` whose lengths are proportional to the
+/// given weights
+///
+/// The first and last boundaries are exactly `lower` and `upper`.
+/// Subdomains need to take their coordinates from these boundaries such that neighbours share
+/// bit-identical edges.
+fn split_proportional<F, M>(lower: F, upper: F, weights: &[usize]) -> Vec<F>
+where
+    F: Copy + Num,
+    M: Arithmetic<F>,
+{
+    let total = M::from_usize(weights.iter().sum());
+    let mut cumulative = 0;
+    let mut res = vec![lower];
+    for w in weights[..weights.len() - 1].iter() {
+        cumulative += w;
+        res.push(lower + (upper - lower) * M::from_usize(cumulative) / total);
     }
+    res.push(upper);
+    res
+}
 
-    #[allow(non_snake_case)]
-    fn generate_rectangles(
-        &self,
-        p: F,
-        rectangle: &Rectangle<F>,
-    ) -> impl IntoIterator<Item = Rectangle<F>> + use<F>
-    where
-        F: 'static + RealField + Copy,
-        F: num_traits::cast::AsPrimitive<usize>,
-        usize: num_traits::cast::AsPrimitive<F>,
-    {
-        let min = rectangle.min;
-        let B = rectangle.max[0] - rectangle.min[0];
-        let A = rectangle.max[1] - rectangle.min[1];
-
-        use Decomposition::*;
-        match self {
-            row(hrow) => {
-                let hrow: F = *hrow;
-                let dx_row = A / hrow;
-                let (n_rows1, n_rows2, n_cols1, n_cols2): (usize, usize, F, F) =
-                    if (p / hrow).round() == p / hrow {
-                        (hrow.as_(), 0, (p / hrow), F::zero())
-                    } else {
-                        (
-                            (p - hrow * (p / hrow).floor()).round().as_(),
-                            (hrow * (p / hrow).ceil() - p).round().as_(),
-                            (p / hrow).ceil(),
-                            (p / hrow).floor(),
-                        )
-                    };
-                let dx_col1 = B / n_cols1;
-                let dx_col2 = B / n_cols2;
-                let n_cols1: usize = n_cols1.as_();
-                let n_cols2: usize = n_cols2.as_();
-
-                let rects1 =
-                    create_rectangles(0..n_rows1, 0..n_cols1, dx_row, dx_col1, min[1], min[0]);
-                let rects2 = create_rectangles(
-                    n_rows1..n_rows1 + n_rows2,
-                    0..n_cols2,
-                    dx_row,
-                    dx_col2,
-                    min[1],
-                    min[0],
-                );
-                rects1.into_iter().chain(rects2)
-            }
-            col(kcol) => {
-                let kcol: F = *kcol;
-                let dx_col = B / kcol;
-                let (n_cols1, n_cols2, n_rows1, n_rows2): (usize, usize, F, F) =
-                    if (p / kcol).round() == p / kcol {
-                        (kcol.as_(), 0, p / kcol, F::zero())
-                    } else {
-                        (
-                            (p - kcol * (p / kcol).floor()).round().as_(),
-                            (kcol * (p / kcol).ceil() - p).round().as_(),
-                            (p / kcol).ceil(),
-                            (p / kcol).floor(),
-                        )
-                    };
-                let dx_row1 = A / n_rows1;
-                let dx_row2 = A / n_rows2;
-                let n_rows1: usize = n_rows1.as_();
-                let n_rows2: usize = n_rows2.as_();
-
-                let rects1 =
-                    create_rectangles(0..n_rows1, 0..n_cols1, dx_row1, dx_col, min[1], min[0]);
-                let rects2 = create_rectangles(
-                    0..n_rows2,
-                    n_cols1..n_cols1 + n_cols2,
-                    dx_row2,
-                    dx_col,
-                    min[1],
-                    min[0],
-                );
-                rects1.into_iter().chain(rects2)
-            }
+/// Subdomains of the given band structure ordered band by band
+///
+/// `split(lower, upper, weights)` returns the boundaries of consecutive parts of
+/// `[lower, upper]` whose lengths are proportional to the given weights.
+/// Bands are weighted by their number of subdomains such that all subdomains have the same area.
+pub(crate) fn band_rectangles<F>(
+    rectangle: &Rectangle<F>,
+    (axis, counts): &(usize, Vec<usize>),
+    split: impl Fn(F, F, &[usize]) -> Vec<F>,
+) -> DecomposedDomain<F>
+where
+    F: Copy,
+{
+    let (axis, other) = (*axis, 1 - axis);
+    let bands = split(rectangle.min[axis], rectangle.max[axis], counts);
+    let mut subdomains = Vec::with_capacity(counts.iter().sum());
+    for (band, &count) in counts.iter().enumerate() {
+        let cuts = split(rectangle.min[other], rectangle.max[other], &vec![1; count]);
+        for cut in cuts.windows(2) {
+            let mut min = rectangle.min;
+            let mut max = rectangle.max;
+            (min[axis], max[axis]) = (bands[band], bands[band + 1]);
+            (min[other], max[other]) = (cut[0], cut[1]);
+            subdomains.push(Rectangle { min, max });
         }
     }
+    subdomains
 }
 
-fn create_rectangles<F>(
-    n_rows_range: std::ops::Range<usize>,
-    n_cols_range: std::ops::Range<usize>,
-    dx_row: F,
-    dx_col: F,
-    x0_row: F,
-    x0_col: F,
-) -> impl IntoIterator<Item = Rectangle<F>>
+/// Subdomains of the given band structure with the coordinates of [kmr_decompose]
+pub(crate) fn kmr_band_rectangles<F>(
+    rectangle: &Rectangle<F>,
+    bands: &(usize, Vec<usize>),
+) -> DecomposedDomain<F>
 where
-    F: 'static + RealField + Copy,
+    F: 'static + Copy + RealField,
     F: num_traits::cast::AsPrimitive<usize>,
     usize: num_traits::cast::AsPrimitive<F>,
 {
-    (n_rows_range).flat_map(move |n| {
-        (n_cols_range).clone().map(move |m| {
-            let n: F = n.as_();
-            let m: F = m.as_();
-            let min = [x0_col + m * dx_col, x0_row + n * dx_row];
-            let max = [
-                x0_col + (m + F::one()) * dx_col,
-                x0_row + (n + F::one()) * dx_row,
-            ];
-            Rectangle { min, max }
-        })
-    })
+    band_rectangles(rectangle, bands, split_proportional::<F, Floats>)
 }
 
 /// Partitions a rectangle into multiple smaller rectangles
@@ -219,6 +234,11 @@ where
 ///
 /// This algorithms divides a given rectangle into multiple smaller rectangles and minimizes the
 /// maximum rectangle perimeter.
+/// The rectangle is divided into bands which are as thick as needed such that all subdomains have
+/// the same area.
+/// Among all such band structures, the one with the smallest maximum perimeter is chosen.
+/// Neighbouring subdomains share bit-identical edge coordinates and the outer edges are exactly
+/// the edges of the given rectangle such that the subdomains tile it without gaps or overlaps.
 ///
 /// ```
 /// use spatial_decomposition::{kmr_decompose, Rectangle};
@@ -298,57 +318,169 @@ where
 ///         max: [90.0, 20.0],
 /// });
 /// ```
-#[allow(non_snake_case)]
 pub fn kmr_decompose<F>(rectangle: &Rectangle<F>, n_subdomains: NonZeroUsize) -> DecomposedDomain<F>
 where
     F: 'static + Copy + RealField,
     F: num_traits::cast::AsPrimitive<usize>,
     usize: num_traits::cast::AsPrimitive<F>,
 {
-    let n_subdomains = n_subdomains.get();
-    if n_subdomains == 1 {
-        return vec![rectangle.clone()];
-    }
+    let bands = kmr_bands(rectangle.lengths(), n_subdomains.get());
+    kmr_band_rectangles(rectangle, &bands)
+}
 
-    // Cover the very wide/long cases where n_subdomain < max(a/b, b/a)
-    //                  a
-    //   ┌─────────┬─────────┬─────────┐
-    //   │         │         │         │
-    // b │         │         │         │
-    //   │         │         │         │
-    //   └─────────┴─────────┴─────────┘
-    let n_subdomains_float: F = n_subdomains.as_();
-    let B = rectangle.max[0] - rectangle.min[0];
-    let A = rectangle.max[1] - rectangle.min[1];
-    let ratio_max = (B / A).max(A / B);
-    if n_subdomains_float <= ratio_max {
-        if B >= A {
-            let dx = B / n_subdomains_float;
-            return (0..n_subdomains)
-                .map(|n| Rectangle {
-                    min: [rectangle.min[0] + n.as_() * dx, rectangle.min[1]],
-                    max: [rectangle.min[0] + (n + 1).as_() * dx, rectangle.max[1]],
-                })
-                .collect();
-        } else {
-            let dx = A / n_subdomains_float;
-            return (0..n_subdomains)
-                .map(|n| Rectangle {
-                    min: [rectangle.min[0], rectangle.min[1] + n.as_() * dx],
-                    max: [rectangle.max[0], rectangle.min[1] + (n + 1).as_() * dx],
-                })
-                .collect();
-        };
-    }
+/// Partitions a rectangle with rational coordinates exactly
+///
+/// Works like [kmr_decompose] but the perimeters of all band structures and all coordinates are
+/// computed with exact rational arithmetic.
+/// Comparisons close to ties can thus not be decided wrongly due to rounding errors.
+/// Floats are only needed when converting the resulting coordinates.
+/// The subdomains are ordered band by band.
+/// Integer types with few bits may overflow for large numbers of subdomains or coordinates with
+/// large denominators.
+///
+/// This function requires the `rational` feature.
+///
+/// ```
+/// use num_rational::Rational64;
+/// use num_traits::ToPrimitive;
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [Rational64::from_integer(0); 2],
+///     max: [Rational64::from_integer(5), Rational64::from_integer(3)],
+/// };
+/// let subdomains = kmr_decompose_exact(&domain, 7.try_into().unwrap());
+/// assert_eq!(subdomains.len(), 7);
+/// assert_eq!(subdomains[0].max, [Rational64::new(5, 4), Rational64::new(12, 7)]);
+///
+/// // Convert to floats as the very last step
+/// let subdomains: Vec<Rectangle<f64>> = subdomains
+///     .iter()
+///     .map(|s| Rectangle {
+///         min: s.min.map(|x| x.to_f64().unwrap()),
+///         max: s.max.map(|x| x.to_f64().unwrap()),
+///     })
+///     .collect();
+/// assert_eq!(subdomains[6].max, [5.0, 3.0]);
+/// ```
+#[cfg(feature = "rational")]
+pub fn kmr_decompose_exact<T>(
+    rectangle: &Rectangle<Ratio<T>>,
+    n_subdomains: NonZeroUsize,
+) -> DecomposedDomain<Ratio<T>>
+where
+    T: Copy + num_integer::Integer + num_traits::FromPrimitive,
+{
+    let bands = layout::<_, Rationals>(rectangle.lengths(), n_subdomains.get());
+    band_rectangles(rectangle, &bands, split_proportional::<_, Rationals>)
+}
 
-    let kmr_values = KongMountRoscoeValues::calculate(A, B, n_subdomains);
-    let decomposition = Decomposition::figure_out(&kmr_values);
+/// Quantity minimized by [decompose_with_objective]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Objective {
+    /// Largest perimeter of any subdomain as minimized by [kmr_decompose]
+    MaxPerimeter,
+    /// Total length of all interfaces between subdomains which is proportional to the
+    /// communication volume
+    TotalInterface,
+    /// Largest ratio of the longer to the shorter side of any subdomain
+    MaxAspectRatio,
+}
 
-    decomposition
-        .map(|d| d.generate_rectangles(n_subdomains_float, rectangle))
-        .unwrap()
-        .into_iter()
-        .collect()
+/// Layout chosen by [decompose_with_objective]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layout {
+    /// Bands stacked along the given axis where band `i` contains `counts[i]` subdomains
+    ///
+    /// The first bands contain one subdomain more than the last ones.
+    Bands { axis: usize, counts: Vec<usize> },
+    /// Regular grid with `counts[i]` subdomains along axis `i`
+    Grid { counts: [usize; 2] },
+}
+
+/// Result of [decompose_with_objective]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectiveDecomposition<F> {
+    /// Subdomains ordered band by band
+    pub subdomains: DecomposedDomain<F>,
+    /// Layout of the subdomains
+    pub layout: Layout,
+    /// Value of the objective for the subdomains
+    pub value: F,
+}
+
+/// Partitions a rectangle into subdomains of equal area which minimize the given objective
+///
+/// Searches the band structures of [kmr_decompose] with any number of bands along either axis.
+/// These include all regular grids whose number of cells equals the number of subdomains.
+/// If all bands contain the same number of subdomains, the cuts of neighbouring bands are
+/// aligned and the layout is reported as [Layout::Grid].
+/// For [Objective::MaxPerimeter], the subdomains are identical to the ones of [kmr_decompose].
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 50.0],
+/// };
+/// let n_subdomains = 5.try_into().unwrap();
+/// let perimeter = decompose_with_objective(&domain, n_subdomains, Objective::MaxPerimeter);
+/// assert_eq!(perimeter.subdomains, kmr_decompose(&domain, n_subdomains));
+/// assert_eq!(perimeter.layout, Layout::Grid { counts: [5, 1] });
+/// assert_eq!(perimeter.value, 140.0);
+///
+/// // Two rows have shorter interfaces than five strips with 4 * 50 = 200
+/// let interface = decompose_with_objective(&domain, n_subdomains, Objective::TotalInterface);
+/// assert_eq!(interface.layout, Layout::Bands { axis: 1, counts: vec![3, 2] });
+/// assert_eq!(interface.value, 180.0);
+/// ```
+pub fn decompose_with_objective<F>(
+    rectangle: &Rectangle<F>,
+    n_subdomains: NonZeroUsize,
+    objective: Objective,
+) -> ObjectiveDecomposition<F>
+where
+    F: 'static + Copy + RealField,
+    F: num_traits::cast::AsPrimitive<usize>,
+    usize: num_traits::cast::AsPrimitive<F>,
+{
+    let lengths = rectangle.lengths();
+    let p = n_subdomains.get();
+    let bands = match objective {
+        Objective::MaxPerimeter => kmr_bands(lengths, p),
+        Objective::TotalInterface => {
+            best_bands(p, |bands| interface_length::<F, Floats>(lengths, bands, p))
+        }
+        Objective::MaxAspectRatio => {
+            best_bands(p, |bands| aspect_ratio::<F, Floats>(lengths, bands, p))
+        }
+    };
+    let two = F::one() + F::one();
+    let value = match objective {
+        Objective::MaxPerimeter => two * half_perimeter::<F, Floats>(lengths, &bands, p),
+        Objective::TotalInterface => interface_length::<F, Floats>(lengths, &bands, p),
+        Objective::MaxAspectRatio => aspect_ratio::<F, Floats>(lengths, &bands, p),
+    };
+    let subdomains = band_rectangles(rectangle, &bands, split_proportional::<F, Floats>);
+    let (axis, counts) = bands;
+    let layout = match counts.iter().all(|&n| n == counts[0]) {
+        true if axis == 1 => Layout::Grid {
+            counts: [counts[0], counts.len()],
+        },
+        true => Layout::Grid {
+            counts: [counts.len(), counts[0]],
+        },
+        false => Layout::Bands { axis, counts },
+    };
+    ObjectiveDecomposition {
+        subdomains,
+        layout,
+        value,
+    }
 }
 
 /// Returned from decomposition methods.
@@ -361,18 +493,46 @@ pub enum Error {
     Decompose,
     #[error("Could not assign index to subdomain")]
     Indexing,
+    #[error("Vertices do not form a simple rectilinear polygon")]
+    Polygon,
+    #[error("Obstacles are invalid or cover the whole domain")]
+    Obstacle,
+    #[error("Pieces of composite domain overlap")]
+    Overlap,
+    #[error("Decomposition does not contain any subdomains")]
+    Empty,
+    #[error("Subdomains do not form a band layout")]
+    Layout,
+    #[error("Loads do not match subdomains or are negative")]
+    Load,
+    #[error("Topology does not match number of subdomains")]
+    Topology,
+    #[error("Dimension is not supported")]
+    Dimension,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid GeoJSON: {0}")]
+    GeoJson(String),
+    #[error("Columns have different lengths")]
+    Columns,
+    #[error("Intersections are out of bounds or not sorted")]
+    Intersections,
 }
 
 /// Returned from digitization methods
-pub type SortedDigits<F, I> = Vec<(Rectangle<F>, Vec<(I, Rectangle<F>)>)>;
+///
+/// Subdomains are [Rectangle]s by default but may also be given by any other
+/// [Subdomain](crate::Subdomain) such as a [CompositeDomain](crate::CompositeDomain).
+pub type SortedDigits<F, I, S = Rectangle<F>> = Vec<(S, Vec<(I, Rectangle<F>)>)>;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Assign given digits to a number of subdomains generated by the [kmr_decompose] method.
 ///
 /// It is the first algorithm presented in their paper.
-/// This particular implementation is `O(n*p)` where `n` is the number of digits and `p` is the
-/// number of subdomains generated.
+/// The subdomains are indexed by a [KdTree](crate::KdTree) such that this implementation is
+/// `O((n + p) log p)` where `n` is the number of digits and `p` is the number of subdomains
+/// generated.
 pub fn kmr_digitize_1<F, I>(
     rectangle: &Rectangle<F>,
     n_subdomains: NonZeroUsize,
@@ -384,44 +544,25 @@ where
     usize: num_traits::cast::AsPrimitive<F>,
     I: 'static,
 {
-    let subdomains = kmr_decompose(rectangle, n_subdomains);
-    let mut res: Vec<_> = subdomains
-        .iter()
-        .map(|subdomain| (subdomain.clone(), Vec::new()))
-        .collect();
-    for (digit, rect) in digits.into_iter() {
-        let index = kmr_digitize_1_single(&subdomains, &rect)?;
-        res[index].1.push((digit, rect));
-    }
-    Ok(res)
+    crate::digitize(kmr_decompose(rectangle, n_subdomains), digits)
 }
 
 /// Assigns an index to a given subspace.
 ///
 /// Singular version of [kmr_digitize_1].
-pub fn kmr_digitize_1_single<F>(
-    decomposed_domain: &DecomposedDomain<F>,
+/// Subdomains are not restricted to the output of [kmr_decompose] but can be any
+/// [Subdomain](crate::Subdomain).
+pub fn kmr_digitize_1_single<F, S>(
+    decomposed_domain: &[S],
     subspace: &Rectangle<F>,
 ) -> Result<usize>
 where
     F: 'static + Copy + RealField,
     F: num_traits::cast::AsPrimitive<usize>,
     usize: num_traits::cast::AsPrimitive<F>,
+    S: crate::Subdomain<F, 2>,
 {
-    for (n_subdomain, subdomain) in decomposed_domain.iter().enumerate() {
-        let middle = [
-            (subspace.min[0] + subspace.max[0]) / (F::one() + F::one()),
-            (subspace.min[1] + subspace.max[1]) / (F::one() + F::one()),
-        ];
-        if middle[0] <= subdomain.max[0]
-            && middle[1] <= subdomain.max[1]
-            && middle[0] >= subdomain.min[0]
-            && middle[1] >= subdomain.min[1]
-        {
-            return Ok(n_subdomain);
-        }
-    }
-    Err(Error::Indexing)
+    crate::digitize_single(decomposed_domain, subspace)
 }
 
 #[test]
@@ -518,22 +659,25 @@ fn kmr_decompose_5x3_in_7() {
     let rects = kmr_decompose(&rectangle, 7.try_into().unwrap());
     assert_eq!(rects.len(), 7);
 
+    // Rows are as thick as needed such that all subdomains have the same area
+    let y = 3. * 4. / 7.;
     for i in 0..4 {
-        let dx = (rectangle.max[0] - rectangle.min[0]) / 4.;
         let i = i as f64;
         assert!(rects.contains(&Rectangle {
-            min: [i * dx, 0.],
-            max: [(i + 1.) * dx, 1.5],
+            min: [5. * i / 4., 0.],
+            max: [5. * (i + 1.) / 4., y],
         }));
     }
     for i in 0..3 {
-        let dx = (rectangle.max[0] - rectangle.min[0]) / 3.;
         let i = i as f64;
         assert!(rects.contains(&Rectangle {
-            min: [i * dx, 1.5],
-            max: [(i + 1.) * dx, 3.]
+            min: [5. * i / 3., y],
+            max: [5. * (i + 1.) / 3., 3.]
         }))
     }
+    for r in rects {
+        approx::assert_relative_eq!(r.volume(), 15. / 7., max_relative = 1e-12);
+    }
 }
 
 #[test]
@@ -544,30 +688,19 @@ fn kmr_decompose_6x6_in_14() {
     };
     let rects = kmr_decompose(&rectangle, 14.try_into().unwrap());
     assert_eq!(rects.len(), 14);
-    for i in 0..5 {
-        for j in 0..2 {
-            let dx = 60. / 5.;
-            let i = i as f64;
-            let j = j as f64;
-            let dy = 60. / 3.;
+    // Four rows containing 4, 4, 3 and 3 subdomains
+    // Three rows of 5, 5 and 4 subdomains with equal areas have a larger maximum perimeter
+    let rows = [0., 60. * 4. / 14., 60. * 8. / 14., 60. * 11. / 14., 60.];
+    for (j, n) in [4, 4, 3, 3].into_iter().enumerate() {
+        for i in 0..n {
+            let (i, n) = (i as f64, n as f64);
             let r = Rectangle {
-                min: [i * dx - 60., j * dy],
-                max: [(i + 1.) * dx - 60., (j + 1.) * dy],
+                min: [-60. + 60. * i / n, rows[j]],
+                max: [-60. + 60. * (i + 1.) / n, rows[j + 1]],
             };
             assert!(rects.contains(&r));
         }
     }
-    for i in 0..4 {
-        let i = i as f64;
-        let j = 2.;
-        let dx = 60. / 4.;
-        let dy = 60. / 3.;
-        let r = Rectangle {
-            min: [i * dx - 60., j * dy],
-            max: [(i + 1.) * dx - 60., (j + 1.) * dy],
-        };
-        assert!(rects.contains(&r));
-    }
 }
 
 #[test]
@@ -607,24 +740,274 @@ fn kmr_decompose_square_into_5() {
     for s in subdomains.iter() {
         println!("{s:7.2?}");
     }
+    // Rows and columns of 3 and 2 subdomains are equally good and rows are preferred.
+    // The row of 3 subdomains is thicker such that all subdomains have the same area.
     assert!(subdomains.contains(&Rectangle {
         min: [0.0; 2],
-        max: [50.0, 100.0 / 3.],
+        max: [100.0 / 3., 60.0],
     }));
     assert!(subdomains.contains(&Rectangle {
-        min: [0.0, 100. / 3.],
-        max: [50., 100. / 3. * 2.]
+        min: [100. / 3., 0.0],
+        max: [100. * 2. / 3., 60.]
     }));
     assert!(subdomains.contains(&Rectangle {
-        min: [0.0, 100. / 3. * 2.],
-        max: [50., 100.]
+        min: [100. * 2. / 3., 0.0],
+        max: [100., 60.]
     }));
     assert!(subdomains.contains(&Rectangle {
-        min: [50.0, 0.],
-        max: [100., 50.]
+        min: [0., 60.0],
+        max: [50., 100.]
     }));
     assert!(subdomains.contains(&Rectangle {
-        min: [50.0, 50.],
+        min: [50., 60.0],
         max: [100., 100.]
     }));
 }
+
+#[cfg(feature = "rational")]
+#[test]
+fn kmr_decompose_exact_matches_float() {
+    use num_rational::Rational64;
+    use num_traits::ToPrimitive;
+    let domain = Rectangle {
+        min: [-3.0, 2.0],
+        max: [97.0, 102.0],
+    };
+    let exact_domain = Rectangle {
+        min: domain.min.map(|x| Rational64::from_integer(x as i64)),
+        max: domain.max.map(|x| Rational64::from_integer(x as i64)),
+    };
+    for p in 1..=40 {
+        let subdomains = kmr_decompose(&domain, p.try_into().unwrap());
+        let exact = kmr_decompose_exact(&exact_domain, p.try_into().unwrap());
+        assert_eq!(exact.len(), p);
+        let area = exact
+            .iter()
+            .fold(Rational64::from_integer(0), |acc, s| acc + s.volume());
+        assert_eq!(area, exact_domain.volume());
+        assert!(
+            crate::verify_partition(&exact_domain, &exact, Rational64::from_integer(0)).is_empty()
+        );
+        for s in exact.iter() {
+            let s = Rectangle {
+                min: s.min.map(|x| x.to_f64().unwrap()),
+                max: s.max.map(|x| x.to_f64().unwrap()),
+            };
+            assert!(
+                subdomains
+                    .iter()
+                    .any(|t| approx::abs_diff_eq!(&s, t, epsilon = 1e-10))
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+fn assert_watertight<F>(domain: &Rectangle<F>, subdomains: &[Rectangle<F>])
+where
+    F: Copy + RealField,
+{
+    // Band layouts can only be constructed if neighbours share their edges exactly
+    let layout = crate::BandLayout::from_rectangles(subdomains).unwrap();
+    let axis = layout.axis();
+    let bands = layout.band_boundaries();
+    assert_eq!(bands[0], domain.min[axis]);
+    assert_eq!(bands[bands.len() - 1], domain.max[axis]);
+    for cuts in layout.cuts() {
+        assert_eq!(cuts[0], domain.min[1 - axis]);
+        assert_eq!(cuts[cuts.len() - 1], domain.max[1 - axis]);
+    }
+}
+
+#[test]
+fn kmr_decompose_watertight() {
+    let domain = Rectangle {
+        min: [0.1, 0.3],
+        max: [10.7, 10.9],
+    };
+    for p in 1..=40 {
+        assert_watertight(&domain, &kmr_decompose(&domain, p.try_into().unwrap()));
+    }
+    let domain = Rectangle {
+        min: [-1.3f32, 2.2],
+        max: [98.7, 37.3],
+    };
+    for p in 1..=11 {
+        assert_watertight(&domain, &kmr_decompose(&domain, p.try_into().unwrap()));
+    }
+}
+
+#[test]
+fn kmr_decompose_missing_cases() {
+    // The case analysis of the paper does not find a decomposition for these domains
+    for (max, n_subdomains) in [
+        ([1.0, 1.0], 2),
+        ([40.0, 17.0], 10),
+        ([40.0, 17.0], 24),
+        ([60.0, 40.0], 15),
+        ([100.0, 70.0], 54),
+    ] {
+        let domain = Rectangle { min: [0.0; 2], max };
+        let subdomains = kmr_decompose(&domain, n_subdomains.try_into().unwrap());
+        assert_eq!(subdomains.len(), n_subdomains);
+        assert_watertight(&domain, &subdomains);
+    }
+}
+
+/// Random domain with aspect ratios between `1:100` and `100:1`, offsets up to `10^6` and side
+/// lengths between `10^-6` and `10^6`, together with up to 1000 subdomains
+#[cfg(test)]
+#[derive(Clone, Debug)]
+struct KmrCase {
+    domain: Rectangle<f64>,
+    n_subdomains: usize,
+}
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for KmrCase {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let mut unit = || u32::arbitrary(g) as f64 / u32::MAX as f64;
+        let ratio = 10f64.powf(4.0 * unit() - 2.0);
+        let scale = 10f64.powf(12.0 * unit() - 6.0);
+        let min = [2e6 * unit() - 1e6, 2e6 * unit() - 1e6];
+        let lengths = [scale * ratio.sqrt(), scale / ratio.sqrt()];
+        KmrCase {
+            domain: Rectangle {
+                min,
+                max: [min[0] + lengths[0], min[1] + lengths[1]],
+            },
+            n_subdomains: 1 + (unit() * 999.0).round() as usize,
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let domain = self.domain.clone();
+        Box::new(
+            [self.n_subdomains / 2, self.n_subdomains - 1]
+                .into_iter()
+                .filter(|&n| n > 0)
+                .map(move |n_subdomains| KmrCase {
+                    domain: domain.clone(),
+                    n_subdomains,
+                }),
+        )
+    }
+}
+
+#[test]
+fn kmr_decompose_properties() {
+    fn property(case: KmrCase) -> bool {
+        let KmrCase {
+            domain,
+            n_subdomains,
+        } = case;
+        let subdomains = kmr_decompose(&domain, n_subdomains.try_into().unwrap());
+        let [b, a] = domain.lengths();
+        let area = a * b / n_subdomains as f64;
+
+        // Coordinates are rounded relative to the offset of the domain
+        let magnitude = domain
+            .min
+            .iter()
+            .chain(domain.max.iter())
+            .fold(0f64, |m, x| m.max(x.abs()));
+        let epsilon = 16.0 * f64::EPSILON * magnitude + 1e-12 * (a + b);
+
+        // Subdomains cover the domain without overlaps
+        let partition = crate::verify_partition(&domain, &subdomains, epsilon).is_empty();
+
+        let equal_area = subdomains
+            .iter()
+            .all(|s| (s.volume() - area).abs() <= epsilon * s.surface());
+
+        // No subdomain of this area which fits into the domain can have a smaller perimeter
+        let side = area.sqrt().min(a).min(b);
+        let lower_bound = 2.0 * (side + area / side);
+        let perimeter = subdomains.iter().map(|s| s.surface()).fold(0.0, f64::max);
+        // An aspect ratio of 3 increases the perimeter by a factor of 2/sqrt(3)
+        let bounded = lower_bound - 4.0 * epsilon <= perimeter
+            && perimeter <= 2.0 / 3f64.sqrt() * lower_bound + 4.0 * epsilon;
+
+        // Band structures are close to the best guillotine partition which is found
+        // exhaustively for a few subdomains
+        let max_perimeter = |subdomains: &[Rectangle<f64>]| {
+            subdomains.iter().map(|s| s.surface()).fold(0.0, f64::max)
+        };
+        let p = (1 + n_subdomains % 12).try_into().unwrap();
+        let reference = max_perimeter(&crate::reference::guillotine_decompose(&domain, p));
+        let kmr = max_perimeter(&kmr_decompose(&domain, p));
+        let optimal = reference - 4.0 * epsilon <= kmr && kmr <= 1.1 * reference + 4.0 * epsilon;
+
+        subdomains.len() == n_subdomains && partition && equal_area && bounded && optimal
+    }
+    quickcheck::QuickCheck::new()
+        .tests(100)
+        .quickcheck(property as fn(KmrCase) -> bool);
+}
+
+#[test]
+fn decompose_with_objective_values() {
+    let domain = Rectangle {
+        min: [-20.0, 5.0],
+        max: [80.0, 105.0],
+    };
+    for p in 1..40 {
+        for objective in [
+            Objective::MaxPerimeter,
+            Objective::TotalInterface,
+            Objective::MaxAspectRatio,
+        ] {
+            let result = decompose_with_objective(&domain, p.try_into().unwrap(), objective);
+            assert_eq!(result.subdomains.len(), p);
+            assert!(crate::verify_partition(&domain, &result.subdomains, 1e-10).is_empty());
+            // The reported value matches the subdomains
+            let value = match objective {
+                Objective::MaxPerimeter => result
+                    .subdomains
+                    .iter()
+                    .map(|s| s.surface())
+                    .fold(0.0, f64::max),
+                Objective::TotalInterface => {
+                    let perimeters: f64 = result.subdomains.iter().map(|s| s.surface()).sum();
+                    (perimeters - 400.0) / 2.0
+                }
+                Objective::MaxAspectRatio => result
+                    .subdomains
+                    .iter()
+                    .map(|s| {
+                        let [w, h] = s.lengths();
+                        w.max(h) / w.min(h)
+                    })
+                    .fold(0.0, f64::max),
+            };
+            approx::assert_relative_eq!(result.value, value, max_relative = 1e-10);
+        }
+    }
+}
+
+#[test]
+fn decompose_with_objective_square_grid() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [60.0; 2],
+    };
+    for objective in [
+        Objective::MaxPerimeter,
+        Objective::TotalInterface,
+        Objective::MaxAspectRatio,
+    ] {
+        let result = decompose_with_objective(&domain, 16.try_into().unwrap(), objective);
+        assert_eq!(result.layout, Layout::Grid { counts: [4, 4] });
+    }
+    let result = decompose_with_objective(&domain, 6.try_into().unwrap(), Objective::MaxPerimeter);
+    assert_eq!(result.layout, Layout::Grid { counts: [3, 2] });
+    let result = decompose_with_objective(
+        &Rectangle {
+            min: [0.0; 2],
+            max: [60.0, 200.0],
+        },
+        6.try_into().unwrap(),
+        Objective::MaxAspectRatio,
+    );
+    assert_eq!(result.layout, Layout::Grid { counts: [1, 6] });
+}
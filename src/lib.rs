@@ -7,6 +7,40 @@
 //! | [Rectangle] | [kmr_decompose] | [Kong, Mount and Roscoe](https://scispace.com/pdf/the-decomposition-of-a-rectangle-into-rectangles-of-minimal-3whu99wjdy.pdf) |
 //! | [Rectangle] | [kmr_digitize_1] | [Kong, Mount and Roscoe](https://scispace.com/pdf/the-decomposition-of-a-rectangle-into-rectangles-of-minimal-3whu99wjdy.pdf) |
 //! | [Rectangle] | [kmr_digitize_1_single] | [Kong, Mount and Roscoe](https://scispace.com/pdf/the-decomposition-of-a-rectangle-into-rectangles-of-minimal-3whu99wjdy.pdf) |
+//! | [Rectangle] | [decompose_with_objective] | Band structures and grids of [kmr_decompose] |
+//! | [Rectangle] | [decompose_hierarchical] | Recursive [kmr_decompose] |
+//! | [Rectangle] | [reference::guillotine_decompose] | Exhaustive search over guillotine partitions |
+//! | [Rectangle] with integer coordinates | [kmr_decompose_integer] | [kmr_decompose] with exact remainders |
+//! | [Cuboid] with integer coordinates | [grid_decompose_integer] | |
+//! | Rectilinear Polygon | [rectilinear_partition] | [Eppstein](https://arxiv.org/abs/0908.3916) |
+//! | Rectilinear Polygon | [rectilinear_decompose] | Orthogonal Recursive Bisection |
+//! | [Rectangle] with holes | [decompose_with_holes] | Orthogonal Recursive Bisection |
+//! | [DecomposedDomain] | [BandLayout::rebalance] | Diffusive boundary movement |
+//! | [DecomposedDomain] | [kmr_repartition] | Hungarian algorithm |
+//! | [DecomposedDomain] | [overlap_matrix] | |
+//! | [DecomposedDomain] | [map_to_ranks] | Greedy graph partitioning |
+//! | [DecomposedDomain] | [relabel] | Hungarian algorithm |
+//! | [DecomposedDomain] | [verify_partition] | |
+//! | [Subdomain] | [digitize] | |
+//! | [Subdomain] | [digitize_single] | |
+//! | [Subdomain] | [digitize_integer] | |
+//! | [Subdomain] | [digitize_points] | |
+//! | [Subdomain] | [digitize_columns] | |
+//!
+//! Any decomposition can be indexed by a [KdTree] for fast point location, box intersection and
+//! nearest-neighbour queries.
+//! Any `2D` decomposition can be printed as Unicode diagram with [BoxDrawing].
+//! Decompositions in up to three dimensions can be exported with [write_vtu] for inspection in
+//! [ParaView](https://www.paraview.org).
+//!
+//! ## Features
+//! | Feature | Description |
+//! |:--- |:--- |
+//! | `serde` | Derives `Serialize` and `Deserialize` for [Cuboid] and all results such as [DecomposedDomain] and [SortedDigits] |
+//! | `svg` | Renders decompositions and digitizations as SVG images without additional dependencies |
+//! | `cli` | Builds the `spatial-decomposition` command-line tool which writes JSON, CSV, SVG or VTK output |
+//! | `geojson` | Reads domains from and writes decompositions to GeoJSON |
+//! | `rational` | Adds `kmr_decompose_exact` which decomposes rectangles with exact rational arithmetic |
 
 use approx::RelativeEq;
 
@@ -29,12 +63,22 @@ use approx::RelativeEq;
 /// ```
 #[derive(Clone, Debug, PartialEq, RelativeEq)]
 #[approx(epsilon_type = F)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "F: serde::Serialize",
+        deserialize = "F: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Cuboid<F, const D: usize> {
     /// Lower bounds of cuboid
     #[approx(into_iter)]
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
     pub min: [F; D],
     /// Upper bounds of cuboid
     #[approx(into_iter)]
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
     pub max: [F; D],
 }
 
@@ -47,6 +91,50 @@ pub type Rectangle<F> = Cuboid<F, 2>;
 /// `1D` variant of the [Cuboid]
 pub type Line<F> = Cuboid<F, 1>;
 
+mod assignment;
+mod balance;
+mod bisection;
+mod box_drawing;
+mod composite;
+mod cuboid;
+mod digitize;
+#[cfg(feature = "geojson")]
+mod geojson;
+mod hierarchical;
+mod integer;
+mod kd_tree;
 mod kong_mount_roscoe;
+mod obstacles;
+mod overlap;
+mod ranks;
+mod rectilinear;
+pub mod reference;
+mod relabel;
+mod repartition;
+#[cfg(feature = "serde")]
+mod serde_array;
+#[cfg(feature = "svg")]
+mod svg;
+mod verify;
+mod vtk;
 
+pub use balance::*;
+pub use box_drawing::*;
+pub use composite::*;
+pub use digitize::*;
+#[cfg(feature = "geojson")]
+pub use geojson::*;
+pub use hierarchical::*;
+pub use integer::*;
+pub use kd_tree::*;
 pub use kong_mount_roscoe::*;
+pub use obstacles::*;
+pub use overlap::*;
+pub use ranks::*;
+pub use rectilinear::*;
+pub use relabel::*;
+pub use repartition::*;
+#[cfg(feature = "svg")]
+pub use svg::*;
+pub use verify::*;
+pub use vtk::*;
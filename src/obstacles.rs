@@ -0,0 +1,217 @@
+use std::num::NonZeroUsize;
+
+use num_traits::AsPrimitive;
+use simba::scalar::RealField;
+
+use crate::{CompositeDomain, DecomposedDomain, Error, Rectangle, Result};
+
+/// Covers the area of the domain which is not occupied by any obstacle with rectangles.
+///
+/// Cells of the grid spanned by all obstacle coordinates are merged into horizontal runs first
+/// and identical runs of consecutive rows are merged afterwards.
+fn free_space<F>(domain: &Rectangle<F>, obstacles: &[Rectangle<F>]) -> DecomposedDomain<F>
+where
+    F: Copy + RealField,
+{
+    let clipped: Vec<_> = obstacles
+        .iter()
+        .filter_map(|o| o.intersection(domain))
+        .filter(|o| o.volume() > F::zero())
+        .collect();
+
+    let coordinates = |i: usize| {
+        let mut values: Vec<F> = [domain.min[i], domain.max[i]]
+            .into_iter()
+            .chain(clipped.iter().flat_map(|o| [o.min[i], o.max[i]]))
+            .collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        values.dedup();
+        values
+    };
+    let xs = coordinates(0);
+    let ys = coordinates(1);
+    let index = |values: &[F], v: F| {
+        values
+            .binary_search_by(|c| c.partial_cmp(&v).unwrap())
+            .unwrap()
+    };
+    let (nx, ny) = (xs.len() - 1, ys.len() - 1);
+    let mut blocked = vec![false; nx * ny];
+    for o in clipped.iter() {
+        for y in index(&ys, o.min[1])..index(&ys, o.max[1]) {
+            for x in index(&xs, o.min[0])..index(&xs, o.max[0]) {
+                blocked[y * nx + x] = true;
+            }
+        }
+    }
+
+    let mut rectangles = Vec::new();
+    // Runs of the previous row which can still be extended upwards
+    let mut open: Vec<(usize, usize, usize)> = Vec::new();
+    for y in 0..=ny {
+        let mut runs = Vec::new();
+        if y < ny {
+            let mut x = 0;
+            while x < nx {
+                if blocked[y * nx + x] {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < nx && !blocked[y * nx + x] {
+                    x += 1;
+                }
+                runs.push((start, x));
+            }
+        }
+        let mut next_open = Vec::new();
+        for (x0, x1, y0) in open.into_iter() {
+            if let Some(pos) = runs.iter().position(|&r| r == (x0, x1)) {
+                runs.remove(pos);
+                next_open.push((x0, x1, y0));
+            } else {
+                rectangles.push(Rectangle {
+                    min: [xs[x0], ys[y0]],
+                    max: [xs[x1], ys[y]],
+                });
+            }
+        }
+        next_open.extend(runs.into_iter().map(|(x0, x1)| (x0, x1, y)));
+        open = next_open;
+    }
+    rectangles
+}
+
+/// Decomposes a rectangle with rectangular holes into subdomains of equal free area.
+///
+/// Obstacles may overlap each other and may extend beyond the domain.
+/// The free area of the domain is covered by rectangles which are then distributed by
+/// orthogonal recursive bisection, similarly to [rectilinear_decompose](crate::rectilinear_decompose).
+/// Every subdomain is thus given as a [CompositeDomain] of rectangles which do not intersect any
+/// obstacle.
+///
+/// ```
+/// use spatial_decomposition::*;
+/// use approx::assert_relative_eq;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 50.0],
+/// };
+/// // Two pillars
+/// let obstacles = [
+///     Rectangle {
+///         min: [20.0, 20.0],
+///         max: [30.0, 30.0],
+///     },
+///     Rectangle {
+///         min: [70.0, 20.0],
+///         max: [80.0, 30.0],
+///     },
+/// ];
+/// let subdomains = decompose_with_holes(&domain, &obstacles, 6.try_into().unwrap())?;
+/// assert_eq!(subdomains.len(), 6);
+/// for subdomain in subdomains {
+///     assert_relative_eq!(subdomain.volume(), 4800.0 / 6.0);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn decompose_with_holes<F>(
+    domain: &Rectangle<F>,
+    obstacles: &[Rectangle<F>],
+    n_subdomains: NonZeroUsize,
+) -> Result<Vec<CompositeDomain<F, 2>>>
+where
+    F: 'static + Copy + RealField,
+    usize: AsPrimitive<F>,
+{
+    let is_valid = |r: &Rectangle<F>| {
+        r.min.iter().chain(r.max.iter()).all(|x| x.is_finite())
+            && r.min[0] <= r.max[0]
+            && r.min[1] <= r.max[1]
+    };
+    if !is_valid(domain) || !obstacles.iter().all(is_valid) {
+        return Err(Error::Obstacle);
+    }
+    let pieces = free_space(domain, obstacles);
+    if pieces.is_empty() {
+        return Err(Error::Obstacle);
+    }
+    Ok(crate::bisection::bisect(pieces, n_subdomains.get())
+        .into_iter()
+        .map(CompositeDomain::from_disjoint)
+        .collect())
+}
+
+#[test]
+fn free_space_covers_domain() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [10.0; 2],
+    };
+    let obstacles = [
+        Rectangle {
+            min: [2.0, 2.0],
+            max: [4.0, 4.0],
+        },
+        Rectangle {
+            min: [3.0, 3.0],
+            max: [5.0, 6.0],
+        },
+        Rectangle {
+            min: [8.0, -2.0],
+            max: [12.0, 1.0],
+        },
+    ];
+    let pieces = free_space(&domain, &obstacles);
+    let area: f64 = pieces.iter().map(|r| r.volume()).sum();
+    assert_eq!(area, 100.0 - 4.0 - 6.0 + 1.0 - 2.0);
+    for piece in pieces.iter() {
+        for o in obstacles.iter() {
+            assert!(piece.intersection(o).is_none_or(|c| c.volume() == 0.0));
+        }
+    }
+}
+
+#[test]
+fn decompose_with_holes_equal_area() {
+    use approx::assert_relative_eq;
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [200.0, 100.0],
+    };
+    // Regular array of pillars
+    let obstacles: Vec<_> = (0..5)
+        .flat_map(|i| {
+            (0..3).map(move |j| Rectangle {
+                min: [20.0 + 40.0 * i as f64, 15.0 + 30.0 * j as f64],
+                max: [30.0 + 40.0 * i as f64, 25.0 + 30.0 * j as f64],
+            })
+        })
+        .collect();
+    let free_area = 20000.0 - 15.0 * 100.0;
+    for n in 1..40 {
+        let subdomains = decompose_with_holes(&domain, &obstacles, n.try_into().unwrap()).unwrap();
+        assert_eq!(subdomains.len(), n);
+        for subdomain in subdomains.iter() {
+            assert_relative_eq!(
+                subdomain.volume(),
+                free_area / n as f64,
+                max_relative = 1e-10
+            );
+        }
+    }
+}
+
+#[test]
+fn decompose_with_holes_fully_blocked() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [1.0; 2],
+    };
+    let obstacles = [Rectangle {
+        min: [-1.0; 2],
+        max: [2.0; 2],
+    }];
+    assert!(decompose_with_holes(&domain, &obstacles, 2.try_into().unwrap()).is_err());
+}
@@ -0,0 +1,222 @@
+use num_traits::Num;
+
+use crate::{Cuboid, KdTree};
+#[cfg(feature = "serde")]
+use crate::{Error, Result};
+
+/// Common region of a subdomain of the old and a subdomain of the new decomposition
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Intersection<F, const D: usize> {
+    /// Index of the subdomain in the old decomposition
+    pub old: usize,
+    /// Index of the subdomain in the new decomposition
+    pub new: usize,
+    /// Intersection of both subdomains
+    pub region: Cuboid<F, D>,
+    /// Volume of the intersection
+    pub volume: F,
+}
+
+/// Sparse matrix of intersection volumes between two decompositions
+///
+/// Rows correspond to the subdomains of the old and columns to the subdomains of the new
+/// decomposition.
+/// Only intersections with positive volume are stored.
+/// See [overlap_matrix].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "OverlapMatrixRaw<F, D>",
+        bound(deserialize = "F: serde::Deserialize<'de>")
+    )
+)]
+pub struct OverlapMatrix<F, const D: usize> {
+    n_old: usize,
+    n_new: usize,
+    intersections: Vec<Intersection<F, D>>,
+}
+
+/// Unchecked [OverlapMatrix] which is validated after deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "F: serde::Deserialize<'de>"))]
+struct OverlapMatrixRaw<F, const D: usize> {
+    n_old: usize,
+    n_new: usize,
+    intersections: Vec<Intersection<F, D>>,
+}
+
+#[cfg(feature = "serde")]
+impl<F, const D: usize> TryFrom<OverlapMatrixRaw<F, D>> for OverlapMatrix<F, D> {
+    type Error = Error;
+
+    /// Checks that all intersections lie inside the matrix and are sorted as required by
+    /// [OverlapMatrix::row] and [OverlapMatrix::get]
+    fn try_from(raw: OverlapMatrixRaw<F, D>) -> Result<Self> {
+        let OverlapMatrixRaw {
+            n_old,
+            n_new,
+            intersections,
+        } = raw;
+        let inside = intersections.iter().all(|i| i.old < n_old && i.new < n_new);
+        let sorted = intersections
+            .windows(2)
+            .all(|w| (w[0].old, w[0].new) < (w[1].old, w[1].new));
+        if !(inside && sorted) {
+            return Err(Error::Intersections);
+        }
+        Ok(OverlapMatrix {
+            n_old,
+            n_new,
+            intersections,
+        })
+    }
+}
+
+impl<F, const D: usize> OverlapMatrix<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    /// Number of old (rows) and new (columns) subdomains
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n_old, self.n_new)
+    }
+
+    /// All non-empty intersections sorted by the index of the old and then the new subdomain
+    pub fn intersections(&self) -> &[Intersection<F, D>] {
+        &self.intersections
+    }
+
+    /// Non-empty intersections of the given old subdomain sorted by the index of the new
+    /// subdomain
+    pub fn row(&self, old: usize) -> &[Intersection<F, D>] {
+        let start = self.intersections.partition_point(|i| i.old < old);
+        let end = self.intersections.partition_point(|i| i.old <= old);
+        &self.intersections[start..end]
+    }
+
+    /// Volume of the intersection of the given old and new subdomain
+    pub fn get(&self, old: usize, new: usize) -> F {
+        let row = self.row(old);
+        row.binary_search_by_key(&new, |i| i.new)
+            .map_or(F::zero(), |n| row[n].volume)
+    }
+
+    /// Converts the sparse matrix into a dense representation
+    pub fn to_dense(&self) -> Vec<Vec<F>> {
+        let mut res = vec![vec![F::zero(); self.n_new]; self.n_old];
+        for i in self.intersections.iter() {
+            res[i.old][i.new] = i.volume;
+        }
+        res
+    }
+}
+
+/// Calculates the intersections between all subdomains of two decompositions.
+///
+/// Both decompositions usually cover the same domain but this is not required.
+/// The subdomains of the new decomposition are indexed by a [KdTree] such that this method is
+/// `O((n + m) log m)` for well-behaved decompositions with `n` old and `m` new subdomains.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [60.0, 30.0],
+/// };
+/// let old = kmr_decompose(&domain, 2.try_into().unwrap());
+/// let new = kmr_decompose(&domain, 3.try_into().unwrap());
+/// let overlap = overlap_matrix(&old, &new);
+///
+/// assert_eq!(overlap.shape(), (2, 3));
+/// assert_eq!(overlap.intersections().len(), 4);
+/// assert_eq!(overlap.get(0, 1), 300.0);
+/// assert_eq!(overlap.get(1, 0), 0.0);
+/// assert_eq!(overlap.row(1)[0].region, Rectangle {
+///     min: [30.0, 0.0],
+///     max: [40.0, 30.0],
+/// });
+/// ```
+pub fn overlap_matrix<F, const D: usize>(
+    old: &[Cuboid<F, D>],
+    new: &[Cuboid<F, D>],
+) -> OverlapMatrix<F, D>
+where
+    F: Copy + PartialOrd + Num,
+{
+    let tree = KdTree::new(new.iter().cloned());
+    let intersections = old
+        .iter()
+        .enumerate()
+        .flat_map(|(n_old, o)| {
+            tree.intersecting(o).into_iter().filter_map(move |n_new| {
+                let region = o.intersection(&new[n_new])?;
+                let volume = region.volume();
+                (volume > F::zero()).then_some(Intersection {
+                    old: n_old,
+                    new: n_new,
+                    region,
+                    volume,
+                })
+            })
+        })
+        .collect();
+    OverlapMatrix {
+        n_old: old.len(),
+        n_new: new.len(),
+        intersections,
+    }
+}
+
+#[test]
+fn overlap_matrix_conserves_volume() {
+    use approx::assert_relative_eq;
+    let domain = crate::Rectangle {
+        min: [-5.0, 3.0],
+        max: [45.0, 33.0],
+    };
+    let old = crate::kmr_decompose(&domain, 7.try_into().unwrap());
+    let new = crate::kmr_decompose(&domain, 13.try_into().unwrap());
+    let overlap = overlap_matrix(&old, &new);
+    let dense = overlap.to_dense();
+    for (n, row) in dense.iter().enumerate() {
+        assert_relative_eq!(
+            row.iter().sum::<f64>(),
+            old[n].volume(),
+            max_relative = 1e-12
+        );
+    }
+    for n in 0..new.len() {
+        let column: f64 = dense.iter().map(|row| row[n]).sum();
+        assert_relative_eq!(column, new[n].volume(), max_relative = 1e-12);
+    }
+    for i in overlap.intersections() {
+        assert!(old[i.old].contains_cuboid(&i.region));
+        assert!(new[i.new].contains_cuboid(&i.region));
+        assert_eq!(overlap.get(i.old, i.new), i.volume);
+    }
+}
+
+#[test]
+fn overlap_matrix_3d() {
+    let old = [Cuboid {
+        min: [0; 3],
+        max: [4; 3],
+    }];
+    let new: Vec<_> = (0..8)
+        .map(|n| {
+            let min = [2 * (n & 1), 2 * ((n >> 1) & 1), 2 * (n >> 2)];
+            Cuboid {
+                min,
+                max: min.map(|x| x + 2),
+            }
+        })
+        .collect();
+    let overlap = overlap_matrix(&old, &new);
+    assert_eq!(overlap.to_dense(), vec![vec![8; 8]]);
+    assert_eq!(overlap_matrix(&new, &old).row(3).len(), 1);
+}
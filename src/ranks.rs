@@ -0,0 +1,379 @@
+use simba::scalar::RealField;
+
+use crate::{Cuboid, Error, KdTree, Result};
+
+/// Shared interface between two subdomains
+///
+/// Stores the indices of both subdomains (smaller index first) and the measure of their common
+/// boundary (length in `2D`, area in `3D`).
+pub type Interface<F> = (usize, usize, F);
+
+/// Calculates all interfaces between adjacent subdomains.
+///
+/// Two subdomains are adjacent if they share a part of their boundary with positive measure.
+/// Subdomains which only touch at a corner or edge are not adjacent.
+/// Interfaces are sorted by the indices of their subdomains.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [90.0, 20.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 3.try_into().unwrap());
+/// assert_eq!(interfaces(&subdomains), vec![(0, 1, 20.0), (1, 2, 20.0)]);
+/// ```
+pub fn interfaces<F, const D: usize>(subdomains: &[Cuboid<F, D>]) -> Vec<Interface<F>>
+where
+    F: Copy + RealField,
+{
+    let tree = KdTree::new(subdomains.iter().cloned());
+    let mut res = Vec::new();
+    for (n, subdomain) in subdomains.iter().enumerate() {
+        for m in tree.intersecting(subdomain) {
+            if m <= n {
+                continue;
+            }
+            let Some(common) = subdomain.intersection(&subdomains[m]) else {
+                continue;
+            };
+            let lengths = common.lengths();
+            let n_flat = lengths.iter().filter(|&&l| l <= F::zero()).count();
+            if n_flat == 1 {
+                let measure = lengths
+                    .into_iter()
+                    .filter(|&l| l > F::zero())
+                    .fold(F::one(), |acc, l| acc * l);
+                res.push((n, m, measure));
+            }
+        }
+    }
+    res
+}
+
+/// Assignment of subdomains to ranks of a hierarchical process topology
+///
+/// See [map_to_ranks].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankTable {
+    /// Rank of every subdomain
+    pub ranks: Vec<usize>,
+    /// Number of entities at every level of the topology, e.g. `[nodes, cores]`
+    pub topology: Vec<usize>,
+}
+
+impl RankTable {
+    /// Position of the rank at every level of the topology
+    ///
+    /// The first level is the most significant, i.e. ranks `0..topology[1]` belong to the first
+    /// node in a `[nodes, cores]` topology.
+    pub fn coordinates(&self, rank: usize) -> Vec<usize> {
+        let mut rank = rank;
+        let mut res = vec![0; self.topology.len()];
+        for (c, &n) in res.iter_mut().zip(self.topology.iter()).rev() {
+            *c = rank % n;
+            rank /= n;
+        }
+        res
+    }
+
+    /// Total measure of all interfaces whose subdomains belong to different entities at the
+    /// given level of the topology
+    ///
+    /// For `level = 0` in a `[nodes, cores]` topology this is the interface between nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is not smaller than the number of levels of the topology or if an
+    /// interface refers to a subdomain without rank.
+    pub fn cut<F>(&self, interfaces: &[Interface<F>], level: usize) -> F
+    where
+        F: Copy + RealField,
+    {
+        let stride: usize = self.topology[level + 1..].iter().product();
+        interfaces
+            .iter()
+            .filter(|(n, m, _)| self.ranks[*n] / stride != self.ranks[*m] / stride)
+            .fold(F::zero(), |acc, (_, _, l)| acc + *l)
+    }
+}
+
+/// Maximum number of passes over all pairs of subdomains when improving groups by swaps
+const SWAP_PASSES: usize = 8;
+
+/// Splits the given subdomains into groups of equal size with minimal interface between the
+/// groups.
+///
+/// Groups are grown greedily from a seed with the fewest free neighbours and afterwards improved
+/// by pairwise swaps of subdomains at the boundaries of the groups.
+fn split_groups<F, const D: usize>(
+    members: &[usize],
+    n_groups: usize,
+    weights: &[Vec<(usize, F)>],
+    subdomains: &[Cuboid<F, D>],
+) -> Vec<Vec<usize>>
+where
+    F: Copy + RealField,
+{
+    let size = members.len() / n_groups;
+    if size == 1 || n_groups == 1 {
+        return members.chunks(size).map(|c| c.to_vec()).collect();
+    }
+    let mut group = vec![usize::MAX; subdomains.len()];
+    let free = usize::MAX - 1;
+    for &n in members {
+        group[n] = free;
+    }
+    let distance = |n: usize, m: usize| {
+        let (a, b) = (subdomains[n].center(), subdomains[m].center());
+        (0..D).fold(F::zero(), |acc, i| acc + (a[i] - b[i]) * (a[i] - b[i]))
+    };
+
+    for g in 0..n_groups {
+        let seed = *members
+            .iter()
+            .filter(|&&n| group[n] == free)
+            .min_by_key(|&&n| weights[n].iter().filter(|(m, _)| group[*m] == free).count())
+            .unwrap();
+        group[seed] = g;
+        let mut connection = vec![F::zero(); subdomains.len()];
+        // Free subdomains which are connected to the group
+        let mut frontier = Vec::new();
+        let mut added = seed;
+        for _ in 1..size {
+            for &(m, w) in weights[added].iter() {
+                connection[m] += w;
+                frontier.push(m);
+            }
+            frontier.retain(|&n| group[n] == free);
+            frontier.sort();
+            frontier.dedup();
+            let candidates = if frontier.is_empty() {
+                members
+            } else {
+                &frontier[..]
+            };
+            added = *candidates
+                .iter()
+                .filter(|&&n| group[n] == free)
+                .min_by(|&&n, &&m| {
+                    (-connection[n], distance(seed, n))
+                        .partial_cmp(&(-connection[m], distance(seed, m)))
+                        .unwrap()
+                })
+                .unwrap();
+            group[added] = g;
+        }
+    }
+
+    // Improve by swapping pairs of subdomains between groups
+    // A swap can only reduce the interface if one of the subdomains touches the group of the
+    // other one such that only pairs of subdomains at the boundary between their groups are
+    // considered.
+    let connection_to = |group: &[usize], n: usize, g: usize| {
+        weights[n]
+            .iter()
+            .filter(|(m, _)| group[*m] == g)
+            .fold(F::zero(), |acc, (_, w)| acc + *w)
+    };
+    for _ in 0..SWAP_PASSES {
+        let mut boundary = vec![Vec::new(); n_groups];
+        for &n in members {
+            if weights[n]
+                .iter()
+                .any(|(m, _)| group[*m] < n_groups && group[*m] != group[n])
+            {
+                boundary[group[n]].push(n);
+            }
+        }
+        let mut improved = false;
+        for a in boundary.concat() {
+            let mut neighbours: Vec<usize> = weights[a]
+                .iter()
+                .map(|(m, _)| group[*m])
+                .filter(|&h| h < n_groups && h != group[a])
+                .collect();
+            neighbours.sort();
+            neighbours.dedup();
+            for h in neighbours {
+                for &b in boundary[h].iter() {
+                    let g = group[a];
+                    if group[b] != h || g == h {
+                        continue;
+                    }
+                    let w_ab = weights[a]
+                        .iter()
+                        .filter(|(m, _)| *m == b)
+                        .fold(F::zero(), |acc, (_, w)| acc + *w);
+                    let gain = connection_to(&group, a, h) - connection_to(&group, a, g)
+                        + connection_to(&group, b, g)
+                        - connection_to(&group, b, h)
+                        - w_ab
+                        - w_ab;
+                    if gain > F::zero() {
+                        group[a] = h;
+                        group[b] = g;
+                        improved = true;
+                    }
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    (0..n_groups)
+        .map(|g| {
+            members
+                .iter()
+                .filter(|&&n| group[n] == g)
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+/// Assigns subdomains to the ranks of a hierarchical process topology.
+///
+/// The topology lists the number of entities at every level, e.g. `[nodes, cores]` for a cluster
+/// of nodes with multiple cores each.
+/// Its product needs to match the number of subdomains.
+/// Subdomains are grouped level by level such that the measure of the [interfaces] between
+/// groups is small, starting with the most expensive (first) level.
+/// The result is a plain table which can be used by any message passing library or thread pool.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let subdomains = kmr_decompose(&domain, 16.try_into().unwrap());
+/// let table = map_to_ranks(&subdomains, &[4, 4])?;
+///
+/// // Every node receives a quarter of the domain
+/// let interfaces = interfaces(&subdomains);
+/// assert_eq!(table.cut(&interfaces, 0), 200.0);
+/// assert_eq!(table.coordinates(table.ranks[5]).len(), 2);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn map_to_ranks<F, const D: usize>(
+    subdomains: &[Cuboid<F, D>],
+    topology: &[usize],
+) -> Result<RankTable>
+where
+    F: Copy + RealField,
+{
+    if topology.contains(&0) || topology.iter().product::<usize>() != subdomains.len() {
+        return Err(Error::Topology);
+    }
+    let mut weights = vec![Vec::new(); subdomains.len()];
+    for (n, m, w) in interfaces(subdomains) {
+        weights[n].push((m, w));
+        weights[m].push((n, w));
+    }
+
+    let mut ranks = vec![0; subdomains.len()];
+    let mut groups = vec![(0..subdomains.len()).collect::<Vec<_>>()];
+    for &n_groups in topology {
+        groups = groups
+            .iter()
+            .flat_map(|g| split_groups(g, n_groups, &weights, subdomains))
+            .collect();
+    }
+    for (rank, group) in groups.iter().enumerate() {
+        for &n in group {
+            ranks[n] = rank;
+        }
+    }
+    Ok(RankTable {
+        ranks,
+        topology: topology.to_vec(),
+    })
+}
+
+#[test]
+fn map_to_ranks_scrambled_grid() {
+    // 4x2 grid of unit squares in scrambled order
+    let order = [5, 2, 7, 0, 3, 6, 1, 4];
+    let subdomains: Vec<_> = order
+        .iter()
+        .map(|&n| {
+            let min = [(n % 4) as f64, (n / 4) as f64];
+            crate::Rectangle {
+                min,
+                max: [min[0] + 1.0, min[1] + 1.0],
+            }
+        })
+        .collect();
+    let interfaces = interfaces(&subdomains);
+    assert_eq!(interfaces.len(), 10);
+
+    let table = map_to_ranks(&subdomains, &[2, 4]).unwrap();
+    assert_eq!(table.cut(&interfaces, 0), 2.0);
+    assert_eq!(table.cut(&interfaces, 1), 10.0);
+    let mut ranks = table.ranks.clone();
+    ranks.sort();
+    assert_eq!(ranks, (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn map_to_ranks_hierarchical() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 24.try_into().unwrap());
+    let interfaces = interfaces(&subdomains);
+    let table = map_to_ranks(&subdomains, &[2, 3, 4]).unwrap();
+    let naive = RankTable {
+        ranks: (0..24).collect(),
+        topology: vec![2, 3, 4],
+    };
+    assert!(table.cut(&interfaces, 0) <= naive.cut(&interfaces, 0));
+    assert!(table.cut(&interfaces, 1) <= naive.cut(&interfaces, 1));
+    assert_eq!(table.coordinates(23), vec![1, 2, 3]);
+    assert!(map_to_ranks(&subdomains, &[5, 5]).is_err());
+}
+
+#[test]
+fn interfaces_3d() {
+    let cuboids = [
+        Cuboid {
+            min: [0.0; 3],
+            max: [1.0; 3],
+        },
+        Cuboid {
+            min: [1.0, 0.0, 0.0],
+            max: [2.0, 1.0, 1.0],
+        },
+        Cuboid {
+            min: [1.0, 1.0, 0.0],
+            max: [2.0, 2.0, 1.0],
+        },
+    ];
+    assert_eq!(interfaces(&cuboids), vec![(0, 1, 1.0), (1, 2, 1.0)]);
+}
+
+#[test]
+fn map_to_ranks_many_subdomains() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0, 70.0],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 1024.try_into().unwrap());
+    let interfaces = interfaces(&subdomains);
+    let table = map_to_ranks(&subdomains, &[32, 32]).unwrap();
+    let naive = RankTable {
+        ranks: (0..1024).collect(),
+        topology: vec![32, 32],
+    };
+    assert!(table.cut(&interfaces, 0) <= naive.cut(&interfaces, 0));
+    let mut ranks = table.ranks.clone();
+    ranks.sort();
+    assert_eq!(ranks, (0..1024).collect::<Vec<_>>());
+}
@@ -0,0 +1,613 @@
+use std::num::NonZeroUsize;
+
+use num_traits::AsPrimitive;
+use simba::scalar::RealField;
+
+use crate::{CompositeDomain, DecomposedDomain, Error, Rectangle, Result};
+
+/// Walls of the grid spanned by all vertex coordinates of a polygon
+///
+/// Vertical walls lie on grid line `x` and span row `y` while horizontal walls lie on grid line
+/// `y` and span column `x`.
+struct Walls {
+    nx: usize,
+    ny: usize,
+    vertical: Vec<bool>,
+    horizontal: Vec<bool>,
+}
+
+impl Walls {
+    fn new(nx: usize, ny: usize) -> Self {
+        Walls {
+            nx,
+            ny,
+            vertical: vec![false; (nx + 1) * ny],
+            horizontal: vec![false; nx * (ny + 1)],
+        }
+    }
+
+    fn vertical(&self, x: usize, y: usize) -> bool {
+        self.vertical[x * self.ny + y]
+    }
+
+    fn horizontal(&self, x: usize, y: usize) -> bool {
+        self.horizontal[y * self.nx + x]
+    }
+
+    /// Checks if a wall starts at the given grid point and points in the given direction
+    fn wall_towards(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> bool {
+        match (dx, dy) {
+            (1, 0) => x < self.nx && self.horizontal(x, y),
+            (-1, 0) => x > 0 && self.horizontal(x - 1, y),
+            (0, 1) => y < self.ny && self.vertical(x, y),
+            (0, -1) => y > 0 && self.vertical(x, y - 1),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Marks the wall between the grid point and its neighbour in the given direction.
+    ///
+    /// Returns `false` if the wall was already present.
+    fn mark(&mut self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> bool {
+        let slot = match (dx, dy) {
+            (1, 0) => &mut self.horizontal[y * self.nx + x],
+            (-1, 0) => &mut self.horizontal[y * self.nx + x - 1],
+            (0, 1) => &mut self.vertical[x * self.ny + y],
+            (0, -1) => &mut self.vertical[x * self.ny + y - 1],
+            _ => unreachable!(),
+        };
+        let was_free = !*slot;
+        *slot = true;
+        was_free
+    }
+
+    /// Walks from the given grid point in a direction until another wall is hit
+    fn walk(&self, start: (usize, usize), dir: (isize, isize)) -> Option<(usize, usize)> {
+        let (mut x, mut y) = start;
+        loop {
+            let xn = x.checked_add_signed(dir.0)?;
+            let yn = y.checked_add_signed(dir.1)?;
+            if xn > self.nx || yn > self.ny {
+                return None;
+            }
+            (x, y) = (xn, yn);
+            let hit = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .filter(|&d| d != (-dir.0, -dir.1))
+                .any(|d| self.wall_towards((x, y), d));
+            if hit {
+                return Some((x, y));
+            }
+        }
+    }
+
+    fn mark_segment(&mut self, start: (usize, usize), end: (usize, usize)) {
+        let dir = (
+            (end.0 as isize - start.0 as isize).signum(),
+            (end.1 as isize - start.1 as isize).signum(),
+        );
+        let mut p = start;
+        while p != end {
+            self.mark(p, dir);
+            p = (
+                p.0.checked_add_signed(dir.0).unwrap(),
+                p.1.checked_add_signed(dir.1).unwrap(),
+            );
+        }
+    }
+}
+
+fn sorted_coordinates<F: Copy + RealField>(values: impl Iterator<Item = F>) -> Vec<F> {
+    let mut values: Vec<_> = values.collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values.dedup();
+    values
+}
+
+fn grid_index<F: Copy + RealField>(coordinates: &[F], value: F) -> usize {
+    coordinates
+        .binary_search_by(|c| c.partial_cmp(&value).unwrap())
+        .unwrap()
+}
+
+/// Removes duplicate and collinear vertices and checks that all edges are axis-parallel.
+fn normalize_polygon<F: Copy + RealField>(vertices: &[[F; 2]]) -> Result<Vec<[F; 2]>> {
+    let mut res: Vec<[F; 2]> = Vec::with_capacity(vertices.len());
+    for v in vertices.iter() {
+        if v.iter().any(|x| !x.is_finite()) {
+            return Err(Error::Polygon);
+        }
+        if res.last() != Some(v) {
+            res.push(*v);
+        }
+    }
+    while res.len() > 1 && res.first() == res.last() {
+        res.pop();
+    }
+
+    let direction = |a: &[F; 2], b: &[F; 2]| -> Result<(isize, isize)> {
+        let sign = |x: F| {
+            if x > F::zero() {
+                1
+            } else if x < F::zero() {
+                -1
+            } else {
+                0
+            }
+        };
+        match (sign(b[0] - a[0]), sign(b[1] - a[1])) {
+            (dx, 0) => Ok((dx, 0)),
+            (0, dy) => Ok((0, dy)),
+            _ => Err(Error::Polygon),
+        }
+    };
+
+    // Drop vertices in the middle of straight edges
+    let mut changed = true;
+    while changed && res.len() >= 3 {
+        changed = false;
+        let n = res.len();
+        for i in 0..n {
+            let d_in = direction(&res[(i + n - 1) % n], &res[i])?;
+            let d_out = direction(&res[i], &res[(i + 1) % n])?;
+            if d_in == d_out {
+                res.remove(i);
+                changed = true;
+                break;
+            } else if d_in == (-d_out.0, -d_out.1) {
+                return Err(Error::Polygon);
+            }
+        }
+    }
+    if res.len() < 4 {
+        return Err(Error::Polygon);
+    }
+    Ok(res)
+}
+
+/// Decomposes a rectilinear polygon into the minimum number of rectangles.
+///
+/// The polygon is given by its vertices in clockwise or counter-clockwise order and all edges
+/// need to be parallel to one of the axes.
+/// The polygon is closed implicitly such that the first vertex does not need to be repeated.
+///
+/// The algorithm cuts along a maximum set of non-intersecting chords connecting two reflex
+/// vertices, which is obtained from a maximum matching in the bipartite intersection graph of
+/// horizontal and vertical chords.
+/// Every remaining reflex vertex is resolved by a vertical cut.
+/// For a polygon without holes with `N` reflex vertices and `L` independent chords, this yields
+/// the optimal number of `N - L + 1` rectangles.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// // ┌───┐
+/// // │   │
+/// // │   └───┐
+/// // │       │
+/// // └───────┘
+/// let rectangles = rectilinear_partition(&[
+///     [0.0, 0.0],
+///     [20.0, 0.0],
+///     [20.0, 10.0],
+///     [10.0, 10.0],
+///     [10.0, 20.0],
+///     [0.0, 20.0],
+/// ])?;
+/// assert_eq!(rectangles.len(), 2);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn rectilinear_partition<F>(vertices: &[[F; 2]]) -> Result<DecomposedDomain<F>>
+where
+    F: Copy + RealField,
+{
+    let mut vertices = normalize_polygon(vertices)?;
+    let n = vertices.len();
+
+    // Orient polygon counter-clockwise
+    let area2 = (0..n).fold(F::zero(), |acc, i| {
+        let [x1, y1] = vertices[i];
+        let [x2, y2] = vertices[(i + 1) % n];
+        acc + x1 * y2 - x2 * y1
+    });
+    if area2 < F::zero() {
+        vertices.reverse();
+    }
+
+    let xs = sorted_coordinates(vertices.iter().map(|v| v[0]));
+    let ys = sorted_coordinates(vertices.iter().map(|v| v[1]));
+    let (nx, ny) = (xs.len() - 1, ys.len() - 1);
+    let points: Vec<(usize, usize)> = vertices
+        .iter()
+        .map(|v| (grid_index(&xs, v[0]), grid_index(&ys, v[1])))
+        .collect();
+
+    // Insert boundary into grid
+    let mut walls = Walls::new(nx, ny);
+    for i in 0..n {
+        let (start, end) = (points[i], points[(i + 1) % n]);
+        let dir = (
+            (end.0 as isize - start.0 as isize).signum(),
+            (end.1 as isize - start.1 as isize).signum(),
+        );
+        let mut p = start;
+        while p != end {
+            if !walls.mark(p, dir) {
+                return Err(Error::Polygon);
+            }
+            p = (
+                p.0.checked_add_signed(dir.0).unwrap(),
+                p.1.checked_add_signed(dir.1).unwrap(),
+            );
+        }
+    }
+
+    // Determine interior cells by counting vertical boundary walls to the left
+    let mut inside = vec![false; nx * ny];
+    for y in 0..ny {
+        let mut parity = false;
+        for x in 0..nx {
+            parity ^= walls.vertical(x, y);
+            inside[y * nx + x] = parity;
+        }
+        if parity ^ walls.vertical(nx, y) {
+            return Err(Error::Polygon);
+        }
+    }
+    let is_inside = |x: usize, y: usize| inside[y * nx + x];
+    for y in 0..=ny {
+        for x in 0..nx {
+            let below = y > 0 && is_inside(x, y - 1);
+            let above = y < ny && is_inside(x, y);
+            if walls.horizontal(x, y) != (below != above) {
+                return Err(Error::Polygon);
+            }
+        }
+    }
+
+    // Reflex vertices together with the directions of the extensions of their edges
+    type Direction = (isize, isize);
+    let reflex: Vec<(usize, Direction, Direction)> = (0..n)
+        .filter_map(|i| {
+            let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let d_in = (
+                (b.0 as isize - a.0 as isize).signum(),
+                (b.1 as isize - a.1 as isize).signum(),
+            );
+            let d_out = (
+                (c.0 as isize - b.0 as isize).signum(),
+                (c.1 as isize - b.1 as isize).signum(),
+            );
+            if d_in.0 * d_out.1 - d_in.1 * d_out.0 < 0 {
+                let back = (-d_out.0, -d_out.1);
+                let (horizontal, vertical) = if d_in.1 == 0 {
+                    (d_in, back)
+                } else {
+                    (back, d_in)
+                };
+                Some((i, horizontal, vertical))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let reflex_at = |p: (usize, usize)| reflex.iter().position(|r| points[r.0] == p);
+
+    // Chords connecting two reflex vertices
+    let mut chords_h = Vec::new();
+    let mut chords_v = Vec::new();
+    for (r, &(i, horizontal, vertical)) in reflex.iter().enumerate() {
+        for (dir, chords) in [(horizontal, &mut chords_h), (vertical, &mut chords_v)] {
+            if let Some(s) = walls.walk(points[i], dir).and_then(reflex_at)
+                && s > r
+            {
+                chords.push((r, s));
+            }
+        }
+    }
+    let intersects = |h: (usize, usize), v: (usize, usize)| {
+        let (h0, h1) = (points[reflex[h.0].0], points[reflex[h.1].0]);
+        let (v0, v1) = (points[reflex[v.0].0], points[reflex[v.1].0]);
+        h0.0.min(h1.0) <= v0.0
+            && v0.0 <= h0.0.max(h1.0)
+            && v0.1.min(v1.1) <= h0.1
+            && h0.1 <= v0.1.max(v1.1)
+    };
+    let adjacency: Vec<Vec<usize>> = chords_h
+        .iter()
+        .map(|&h| {
+            (0..chords_v.len())
+                .filter(|&v| intersects(h, chords_v[v]))
+                .collect()
+        })
+        .collect();
+
+    // Maximum matching followed by König's theorem gives a maximum independent set of chords
+    let mut match_v: Vec<Option<usize>> = vec![None; chords_v.len()];
+    fn augment(
+        h: usize,
+        adjacency: &[Vec<usize>],
+        visited: &mut [bool],
+        match_v: &mut [Option<usize>],
+    ) -> bool {
+        for &v in adjacency[h].iter() {
+            if !visited[v] {
+                visited[v] = true;
+                if match_v[v].is_none_or(|h2| augment(h2, adjacency, visited, match_v)) {
+                    match_v[v] = Some(h);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+    for h in 0..chords_h.len() {
+        augment(
+            h,
+            &adjacency,
+            &mut vec![false; chords_v.len()],
+            &mut match_v,
+        );
+    }
+    let mut reached_h = vec![false; chords_h.len()];
+    let mut reached_v = vec![false; chords_v.len()];
+    let mut stack: Vec<usize> = (0..chords_h.len())
+        .filter(|h| !match_v.contains(&Some(*h)))
+        .collect();
+    while let Some(h) = stack.pop() {
+        if reached_h[h] {
+            continue;
+        }
+        reached_h[h] = true;
+        for &v in adjacency[h].iter() {
+            if !reached_v[v] {
+                reached_v[v] = true;
+                if let Some(h2) = match_v[v] {
+                    stack.push(h2);
+                }
+            }
+        }
+    }
+    let chosen = chords_h
+        .iter()
+        .zip(reached_h)
+        .filter_map(|(c, r)| r.then_some(c))
+        .chain(
+            chords_v
+                .iter()
+                .zip(reached_v)
+                .filter_map(|(c, r)| (!r).then_some(c)),
+        );
+
+    let mut resolved = vec![false; reflex.len()];
+    let mut cuts = Vec::new();
+    for &(r, s) in chosen {
+        resolved[r] = true;
+        resolved[s] = true;
+        cuts.push((points[reflex[r].0], points[reflex[s].0]));
+    }
+    for (start, end) in cuts {
+        walls.mark_segment(start, end);
+    }
+    for (r, &(i, _, vertical)) in reflex.iter().enumerate() {
+        if !resolved[r] {
+            let end = walls.walk(points[i], vertical).ok_or(Error::Polygon)?;
+            walls.mark_segment(points[i], end);
+        }
+    }
+
+    // Collect connected cells into rectangles
+    let mut visited = vec![false; nx * ny];
+    let mut rectangles = Vec::new();
+    for y0 in 0..ny {
+        for x0 in 0..nx {
+            if !is_inside(x0, y0) || visited[y0 * nx + x0] {
+                continue;
+            }
+            let mut stack = vec![(x0, y0)];
+            visited[y0 * nx + x0] = true;
+            let (mut lower, mut upper, mut n_cells) = ((x0, y0), (x0, y0), 0);
+            while let Some((x, y)) = stack.pop() {
+                n_cells += 1;
+                lower = (lower.0.min(x), lower.1.min(y));
+                upper = (upper.0.max(x), upper.1.max(y));
+                let neighbours = [
+                    (x > 0 && !walls.vertical(x, y)).then(|| (x - 1, y)),
+                    (x + 1 < nx && !walls.vertical(x + 1, y)).then(|| (x + 1, y)),
+                    (y > 0 && !walls.horizontal(x, y)).then(|| (x, y - 1)),
+                    (y + 1 < ny && !walls.horizontal(x, y + 1)).then(|| (x, y + 1)),
+                ];
+                for (xn, yn) in neighbours.into_iter().flatten() {
+                    if !visited[yn * nx + xn] {
+                        visited[yn * nx + xn] = true;
+                        stack.push((xn, yn));
+                    }
+                }
+            }
+            if n_cells != (upper.0 - lower.0 + 1) * (upper.1 - lower.1 + 1) {
+                return Err(Error::Polygon);
+            }
+            rectangles.push(Rectangle {
+                min: [xs[lower.0], ys[lower.1]],
+                max: [xs[upper.0 + 1], ys[upper.1 + 1]],
+            });
+        }
+    }
+    Ok(rectangles)
+}
+
+/// Decomposes a rectilinear polygon into subdomains of equal area.
+///
+/// The polygon is first split into the minimum number of rectangles by
+/// [rectilinear_partition].
+/// Afterwards, the rectangles are distributed by orthogonal recursive bisection: the bounding
+/// box of the remaining area is cut perpendicular to its longest side such that the areas on both
+/// sides are proportional to the number of subdomains they receive.
+/// Every subdomain is thus given as a [CompositeDomain] of rectangles.
+///
+/// ```
+/// use spatial_decomposition::*;
+/// use approx::assert_relative_eq;
+///
+/// // Staircase domain with total area 600
+/// let subdomains = rectilinear_decompose(
+///     &[
+///         [0.0, 0.0],
+///         [30.0, 0.0],
+///         [30.0, 10.0],
+///         [20.0, 10.0],
+///         [20.0, 20.0],
+///         [10.0, 20.0],
+///         [10.0, 30.0],
+///         [0.0, 30.0],
+///     ],
+///     4.try_into().unwrap(),
+/// )?;
+/// assert_eq!(subdomains.len(), 4);
+/// for subdomain in subdomains {
+///     assert_relative_eq!(subdomain.volume(), 150.0);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn rectilinear_decompose<F>(
+    vertices: &[[F; 2]],
+    n_subdomains: NonZeroUsize,
+) -> Result<Vec<CompositeDomain<F, 2>>>
+where
+    F: 'static + Copy + RealField,
+    usize: AsPrimitive<F>,
+{
+    let rectangles = rectilinear_partition(vertices)?;
+    Ok(crate::bisection::bisect(rectangles, n_subdomains.get())
+        .into_iter()
+        .map(CompositeDomain::from_disjoint)
+        .collect())
+}
+
+#[cfg(test)]
+fn area(rectangles: &[Rectangle<f64>]) -> f64 {
+    rectangles.iter().map(|r| r.volume()).sum()
+}
+
+#[test]
+fn rectilinear_partition_rectangle() {
+    let rects = rectilinear_partition(&[[0.0, 0.0], [0.0, 3.0], [5.0, 3.0], [5.0, 0.0]]).unwrap();
+    assert_eq!(
+        rects,
+        vec![Rectangle {
+            min: [0.0; 2],
+            max: [5.0, 3.0]
+        }]
+    );
+}
+
+#[test]
+fn rectilinear_partition_collinear_vertices() {
+    let rects = rectilinear_partition(&[
+        [0.0, 0.0],
+        [2.0, 0.0],
+        [5.0, 0.0],
+        [5.0, 3.0],
+        [0.0, 3.0],
+        [0.0, 0.0],
+    ])
+    .unwrap();
+    assert_eq!(rects.len(), 1);
+}
+
+#[test]
+fn rectilinear_partition_uses_chords() {
+    // Cross shaped domain with 4 reflex vertices which are connected by 4 chords.
+    // Only two parallel chords can be chosen which results in 3 rectangles.
+    //     ┌───┐
+    //   ┌─┘   └─┐
+    //   └─┐   ┌─┘
+    //     └───┘
+    let rects = rectilinear_partition(&[
+        [1.0, 0.0],
+        [2.0, 0.0],
+        [2.0, 1.0],
+        [3.0, 1.0],
+        [3.0, 2.0],
+        [2.0, 2.0],
+        [2.0, 3.0],
+        [1.0, 3.0],
+        [1.0, 2.0],
+        [0.0, 2.0],
+        [0.0, 1.0],
+        [1.0, 1.0],
+    ])
+    .unwrap();
+    assert_eq!(rects.len(), 3);
+    assert_eq!(area(&rects), 5.0);
+}
+
+#[test]
+fn rectilinear_partition_comb() {
+    // Comb with 3 teeth pointing upwards and 4 reflex vertices.
+    // The two reflex vertices at the base of the middle tooth are connected by a chord.
+    let vertices = [
+        [0.0, 0.0],
+        [5.0, 0.0],
+        [5.0, 3.0],
+        [4.0, 3.0],
+        [4.0, 1.0],
+        [3.0, 1.0],
+        [3.0, 2.5],
+        [2.0, 2.5],
+        [2.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 3.0],
+        [0.0, 3.0],
+    ];
+    let rects = rectilinear_partition(&vertices).unwrap();
+    assert_eq!(rects.len(), 4);
+    assert_eq!(area(&rects), 3.0 + 3.0 + 3.0 + 1.5);
+
+    // The same holds true if given in clockwise order
+    let mut reversed = vertices;
+    reversed.reverse();
+    assert_eq!(rectilinear_partition(&reversed).unwrap().len(), 4);
+}
+
+#[test]
+fn rectilinear_partition_invalid() {
+    assert!(rectilinear_partition(&[[0.0, 0.0], [1.0, 1.0], [0.0, 1.0]]).is_err());
+    assert!(rectilinear_partition(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]).is_err());
+    // Self-intersecting polygon
+    assert!(
+        rectilinear_partition(&[
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, -1.0],
+            [0.0, -1.0],
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn rectilinear_decompose_balanced() {
+    use approx::assert_relative_eq;
+    let vertices = [
+        [0.0, 0.0],
+        [100.0, 0.0],
+        [100.0, 20.0],
+        [20.0, 20.0],
+        [20.0, 100.0],
+        [0.0, 100.0],
+    ];
+    for n in 1..30 {
+        let subdomains = rectilinear_decompose(&vertices, n.try_into().unwrap()).unwrap();
+        assert_eq!(subdomains.len(), n);
+        for subdomain in subdomains.iter() {
+            assert_relative_eq!(
+                area(subdomain.pieces()),
+                3600.0 / n as f64,
+                max_relative = 1e-10
+            );
+        }
+    }
+}
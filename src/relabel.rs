@@ -0,0 +1,98 @@
+use simba::scalar::RealField;
+
+use crate::{Cuboid, overlap_matrix};
+
+/// Reorders subdomains such that they overlap as much as possible with a reference.
+///
+/// The new subdomain at index `n` is chosen such that the sum of the volumes of the
+/// intersections with the reference subdomain at the same index is maximal.
+/// This is an assignment problem on the [overlap_matrix] which is solved by the Hungarian
+/// algorithm in `O(p^3)`.
+/// If there are more new than reference subdomains, the remaining subdomains are placed at the
+/// end in their original order.
+///
+/// Returns the reordered subdomains together with the permutation where `permutation[n]` is the
+/// original index of the subdomain which is now placed at index `n`.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [90.0, 30.0],
+/// };
+/// let reference = kmr_decompose(&domain, 3.try_into().unwrap());
+/// let mut new = reference.clone();
+/// new.reverse();
+///
+/// let (relabeled, permutation) = relabel(&reference, new);
+/// assert_eq!(relabeled, reference);
+/// assert_eq!(permutation, vec![2, 1, 0]);
+/// ```
+pub fn relabel<F, const D: usize>(
+    reference: &[Cuboid<F, D>],
+    new: Vec<Cuboid<F, D>>,
+) -> (Vec<Cuboid<F, D>>, Vec<usize>)
+where
+    F: Copy + RealField,
+{
+    let n = new.len();
+    let n_reference = reference.len().min(n);
+    // Rows correspond to positions and columns to the new subdomains
+    let mut weights = overlap_matrix(&reference[..n_reference], &new).to_dense();
+    weights.resize(n, vec![F::zero(); n]);
+    let mut permutation = crate::assignment::maximize_weight(&weights);
+    permutation[n_reference..].sort_unstable();
+
+    let mut new: Vec<_> = new.into_iter().map(Some).collect();
+    let relabeled = permutation
+        .iter()
+        .map(|&j| new[j].take().unwrap())
+        .collect();
+    (relabeled, permutation)
+}
+
+#[test]
+fn relabel_after_refinement() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let reference = crate::kmr_decompose(&domain, 4.try_into().unwrap());
+    let new = crate::kmr_decompose(&domain, 9.try_into().unwrap());
+    let (relabeled, permutation) = relabel(&reference, new.clone());
+
+    let mut sorted = permutation.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..9).collect::<Vec<_>>());
+    for (n, &j) in permutation.iter().enumerate() {
+        assert_eq!(relabeled[n], new[j]);
+    }
+    // The corner subdomains of the 3x3 grid overlap completely with the 2x2 grid
+    for n in 0..4 {
+        let overlap = reference[n].intersection(&relabeled[n]).unwrap();
+        assert!(reference[n].contains_cuboid(&relabeled[n]));
+        approx::assert_relative_eq!(overlap.volume(), 100.0 * 100.0 / 9.0);
+    }
+    // Remaining subdomains keep their order
+    assert!(permutation[4..].windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn relabel_coarsening() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0, 10.0],
+    };
+    let reference = crate::kmr_decompose(&domain, 10.try_into().unwrap());
+    let new = crate::kmr_decompose(&domain, 5.try_into().unwrap());
+    let (relabeled, _) = relabel(&reference, new);
+    assert_eq!(relabeled.len(), 5);
+    // Every new subdomain covers two reference subdomains but can only keep one of them
+    let retained: f64 = (0..5)
+        .filter_map(|n| reference[n].intersection(&relabeled[n]))
+        .map(|c| c.volume())
+        .sum();
+    approx::assert_relative_eq!(retained, 300.0);
+    assert!(relabeled[4].contains_cuboid(&reference[4]));
+}
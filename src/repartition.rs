@@ -0,0 +1,171 @@
+use std::num::NonZeroUsize;
+
+use simba::scalar::RealField;
+
+use crate::{
+    DecomposedDomain, Error, Rectangle, Result, kmr_band_rectangles, kmr_bands, overlap_matrix,
+    relabel,
+};
+
+/// Result of [kmr_repartition]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Repartition<F> {
+    /// New subdomains where the index of each subdomain is its new rank
+    pub subdomains: DecomposedDomain<F>,
+    /// Volume which needs to be moved from an old rank (first entry) to a new rank (second entry)
+    ///
+    /// Pairs of identical ranks and pairs without any overlap are omitted.
+    /// The corresponding regions can be obtained from [overlap_matrix](crate::overlap_matrix).
+    pub migration: Vec<(usize, usize, F)>,
+}
+
+fn mirror<F>(
+    rectangles: &[Rectangle<F>],
+    domain: &Rectangle<F>,
+    axes: [bool; 2],
+) -> Vec<Rectangle<F>>
+where
+    F: Copy + RealField,
+{
+    rectangles
+        .iter()
+        .map(|r| {
+            let mut res = r.clone();
+            for (i, &flip) in axes.iter().enumerate() {
+                if flip {
+                    res.min[i] = domain.min[i] + domain.max[i] - r.max[i];
+                    res.max[i] = domain.min[i] + domain.max[i] - r.min[i];
+                }
+            }
+            res
+        })
+        .collect()
+}
+
+/// Decomposes a domain for a new number of ranks while retaining as much data as possible.
+///
+/// The domain is given by the bounding box of the old subdomains which are indexed by their
+/// rank.
+/// The new decomposition is chosen from the output of [kmr_decompose](crate::kmr_decompose) and its mirror images.
+/// Since the order of the bands does not change the perimeters of the subdomains, the bands are
+/// also shifted cyclically.
+/// Its subdomains are then assigned to the new ranks `0..n_subdomains` by [relabel] such that
+/// the total volume which stays on the same rank is maximal.
+/// When the number of ranks is reduced, the ranks `n_subdomains..` are removed.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let old = kmr_decompose(&domain, 9.try_into().unwrap());
+/// let repartition = kmr_repartition(&old, 10.try_into().unwrap())?;
+/// assert_eq!(repartition.subdomains.len(), 10);
+///
+/// // Only a small fraction of the domain changes its rank
+/// let moved: f64 = repartition.migration.iter().map(|(_, _, v)| v).sum();
+/// assert!(moved < 0.2 * 100.0 * 100.0);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn kmr_repartition<F>(
+    old: &DecomposedDomain<F>,
+    n_subdomains: NonZeroUsize,
+) -> Result<Repartition<F>>
+where
+    F: 'static + Copy + RealField,
+    F: num_traits::cast::AsPrimitive<usize>,
+    usize: num_traits::cast::AsPrimitive<F>,
+{
+    let domain = old
+        .iter()
+        .skip(1)
+        .fold(old.first().ok_or(Error::Empty)?.clone(), |acc, r| {
+            acc.bounding_box(r)
+        });
+    let (axis, counts) = kmr_bands(domain.lengths(), n_subdomains.get());
+
+    let mut best: Option<(F, Vec<Rectangle<F>>)> = None;
+    for shift in 0..counts.len() {
+        let mut shifted = counts.clone();
+        shifted.rotate_right(shift);
+        if shift > 0 && shifted == counts {
+            break;
+        }
+        let decomposition = kmr_band_rectangles(&domain, &(axis, shifted));
+        for axes in [[false, false], [true, false], [false, true], [true, true]] {
+            let (candidate, _) = relabel(old, mirror(&decomposition, &domain, axes));
+            let retained = old
+                .iter()
+                .zip(candidate.iter())
+                .filter_map(|(o, c)| o.intersection(c))
+                .fold(F::zero(), |acc, c| acc + c.volume());
+            if best.as_ref().is_none_or(|(r, _)| retained > *r) {
+                best = Some((retained, candidate));
+            }
+        }
+    }
+    let (_, subdomains) = best.unwrap();
+
+    let migration = overlap_matrix(old, &subdomains)
+        .intersections()
+        .iter()
+        .filter(|i| i.old != i.new)
+        .map(|i| (i.old, i.new, i.volume))
+        .collect();
+    Ok(Repartition {
+        subdomains,
+        migration,
+    })
+}
+
+#[test]
+fn kmr_repartition_identity() {
+    let domain = Rectangle {
+        min: [-10.0, 0.0],
+        max: [90.0, 100.0],
+    };
+    for n in 1..20 {
+        let old = crate::kmr_decompose(&domain, n.try_into().unwrap());
+        let repartition = kmr_repartition(&old, n.try_into().unwrap()).unwrap();
+        assert_eq!(repartition.subdomains, old);
+        assert!(repartition.migration.is_empty());
+    }
+}
+
+#[test]
+fn kmr_repartition_conserves_volume() {
+    use approx::assert_relative_eq;
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [60.0, 40.0],
+    };
+    for (n_old, n_new) in [(4, 5), (12, 9), (7, 16), (16, 14), (3, 1)] {
+        let old = crate::kmr_decompose(&domain, n_old.try_into().unwrap());
+        let repartition = kmr_repartition(&old, n_new.try_into().unwrap()).unwrap();
+        assert_eq!(repartition.subdomains.len(), n_new);
+
+        // Retained volume together with migrated volume equals the total volume
+        let retained: f64 = (0..n_old.min(n_new))
+            .filter_map(|i| old[i].intersection(&repartition.subdomains[i]))
+            .map(|c| c.volume())
+            .sum();
+        let moved: f64 = repartition.migration.iter().map(|(_, _, v)| v).sum();
+        assert_relative_eq!(retained + moved, 2400.0, max_relative = 1e-10);
+
+        // The relabeling is at least as good as the plain ordering of kmr_decompose
+        let plain = crate::kmr_decompose(&domain, n_new.try_into().unwrap());
+        let retained_plain: f64 = (0..n_old.min(n_new))
+            .filter_map(|i| old[i].intersection(&plain[i]))
+            .map(|c| c.volume())
+            .sum();
+        assert!(retained >= retained_plain - 1e-10);
+    }
+}
+
+#[test]
+fn kmr_repartition_empty() {
+    assert!(kmr_repartition::<f64>(&Vec::new(), 2.try_into().unwrap()).is_err());
+}
@@ -0,0 +1,183 @@
+//! (De)Serialization of arrays with generic length
+//!
+//! Serde only implements its traits for arrays of fixed sizes up to 32 elements.
+//! Arrays are stored as tuples which results in plain lists for most formats.
+
+use std::marker::PhantomData;
+
+use serde::de::{Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+pub(crate) fn serialize<S, F, const D: usize>(
+    array: &[F; D],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    F: Serialize,
+{
+    let mut tuple = serializer.serialize_tuple(D)?;
+    for x in array.iter() {
+        tuple.serialize_element(x)?;
+    }
+    tuple.end()
+}
+
+struct ArrayVisitor<F, const D: usize>(PhantomData<F>);
+
+impl<'de, F, const D: usize> Visitor<'de> for ArrayVisitor<F, D>
+where
+    F: Deserialize<'de>,
+{
+    type Value = [F; D];
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "an array of length {D}")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(D);
+        while let Some(x) = seq.next_element()? {
+            if values.len() == D {
+                return Err(A::Error::invalid_length(D + 1, &self));
+            }
+            values.push(x);
+        }
+        let n = values.len();
+        values
+            .try_into()
+            .map_err(|_| A::Error::invalid_length(n, &self))
+    }
+}
+
+pub(crate) fn deserialize<'de, De, F, const D: usize>(deserializer: De) -> Result<[F; D], De::Error>
+where
+    De: Deserializer<'de>,
+    F: Deserialize<'de>,
+{
+    deserializer.deserialize_tuple(D, ArrayVisitor(PhantomData))
+}
+
+#[cfg(test)]
+fn roundtrip<T>(value: &T)
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    let mut cbor = Vec::new();
+    ciborium::into_writer(value, &mut cbor).unwrap();
+    assert_eq!(
+        &ciborium::from_reader::<T, _>(cbor.as_slice()).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn serde_cuboid() {
+    let cuboid = crate::Cuboid {
+        min: [0.0, 1.0, 2.0],
+        max: [3.0, 4.5, 6.0],
+    };
+    assert_eq!(
+        serde_json::to_string(&cuboid).unwrap(),
+        r#"{"min":[0.0,1.0,2.0],"max":[3.0,4.5,6.0]}"#
+    );
+    roundtrip(&cuboid);
+    roundtrip(&crate::Cuboid {
+        min: [-3i64; 40],
+        max: [7i64; 40],
+    });
+    assert!(
+        serde_json::from_str::<crate::Rectangle<f64>>(r#"{"min":[0.0],"max":[1.0,1.0]}"#).is_err()
+    );
+    assert!(
+        serde_json::from_str::<crate::Rectangle<f64>>(r#"{"min":[0.0,0.0,0.0],"max":[1.0,1.0]}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn serde_rejects_invalid_results() {
+    let overlapping = r#"{"pieces": [
+        {"min": [0, 0], "max": [2, 2]},
+        {"min": [1, 1], "max": [3, 3]}
+    ]}"#;
+    assert!(serde_json::from_str::<crate::CompositeDomain<f64, 2>>(overlapping).is_err());
+
+    let layout = |positions: &str| {
+        let json = format!(
+            r#"{{"axis": 1, "bands": [0, 1, 2], "cuts": [[0, 1, 2], [0, 2]],
+                "positions": {positions}}}"#
+        );
+        serde_json::from_str::<crate::BandLayout<f64>>(&json)
+    };
+    assert!(layout("[[0, 0], [0, 1], [1, 0]]").is_ok());
+    assert!(layout("[[0, 0], [0, 2], [1, 0]]").is_err());
+    assert!(layout("[[0, 0], [0, 0], [1, 0]]").is_err());
+    assert!(layout("[[0, 0], [1, 0]]").is_err());
+
+    let matrix = |entries: [(usize, usize); 2]| {
+        let intersections: Vec<_> = entries
+            .iter()
+            .map(|(old, new)| {
+                format!(
+                    r#"{{"old": {old}, "new": {new}, "volume": 1,
+                        "region": {{"min": [0, 0], "max": [1, 1]}}}}"#
+                )
+            })
+            .collect();
+        let json = format!(
+            r#"{{"n_old": 2, "n_new": 2, "intersections": [{}]}}"#,
+            intersections.join(",")
+        );
+        serde_json::from_str::<crate::OverlapMatrix<f64, 2>>(&json)
+    };
+    assert!(matrix([(0, 1), (1, 0)]).is_ok());
+    assert!(matrix([(1, 0), (0, 1)]).is_err());
+    assert!(matrix([(0, 1), (2, 0)]).is_err());
+}
+
+#[test]
+fn serde_results() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 7.try_into().unwrap());
+    roundtrip(&subdomains);
+
+    let digits: Vec<_> = (0..10)
+        .map(|i| {
+            let min = [10.0 * i as f64, 5.0];
+            (
+                i,
+                crate::Rectangle {
+                    min,
+                    max: [min[0] + 10.0, 15.0],
+                },
+            )
+        })
+        .collect();
+    let sorted: crate::SortedDigits<f64, usize> =
+        crate::digitize(subdomains.clone(), digits).unwrap();
+    roundtrip(&sorted);
+
+    let composite = crate::CompositeDomain::new(subdomains[..3].to_vec()).unwrap();
+    roundtrip(&composite);
+    roundtrip(&crate::kmr_repartition(&subdomains, 9.try_into().unwrap()).unwrap());
+    roundtrip(&crate::overlap_matrix(
+        &subdomains,
+        &crate::kmr_decompose(&domain, 5.try_into().unwrap()),
+    ));
+    roundtrip(&crate::BandLayout::from_rectangles(&subdomains).unwrap());
+    roundtrip(&crate::decompose_hierarchical(
+        &domain,
+        &[2.try_into().unwrap(), 3.try_into().unwrap()],
+    ));
+    roundtrip(&crate::map_to_ranks(&subdomains, &[7]).unwrap());
+}
@@ -0,0 +1,314 @@
+use std::fmt::Write;
+
+use num_traits::{AsPrimitive, Num};
+
+use crate::{Rectangle, SortedDigits, Subdomain};
+
+/// Settings for [decomposition_svg] and [digitization_svg]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// Width of the image in pixels
+    ///
+    /// The height is chosen such that the aspect ratio of the domain is preserved.
+    pub width: f64,
+    /// Writes the index of every subdomain at its center
+    pub labels: bool,
+    /// Draws the outline of every digit of a digitization
+    pub digit_outlines: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            width: 1000.0,
+            labels: false,
+            digit_outlines: true,
+        }
+    }
+}
+
+/// Dark colormap going from black over blue to white
+///
+/// This is the same colormap as the `Bone` colormap of
+/// [plotters](https://docs.rs/plotters) which is used in the examples.
+fn bone(t: f64) -> [u8; 3] {
+    const COLORS: [[f64; 3]; 3] = [[0.0, 0.0, 0.0], [0.0, 0.0, 255.0], [255.0, 255.0, 255.0]];
+    let x = t.clamp(0.0, 1.0) * (COLORS.len() - 1) as f64;
+    let (lower, upper) = (x.floor() as usize, x.ceil() as usize);
+    let r = x.ceil() - x;
+    std::array::from_fn(|i| ((1.0 - r) * COLORS[upper][i] + r * COLORS[lower][i]).round() as u8)
+}
+
+/// Maps coordinates of the domain onto the image with the `y`-axis pointing upwards
+struct Canvas {
+    min: [f64; 2],
+    max: [f64; 2],
+    scale: f64,
+    svg: String,
+}
+
+impl Canvas {
+    fn new<'a, F>(rectangles: impl IntoIterator<Item = &'a Rectangle<F>>, width: f64) -> Self
+    where
+        F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    {
+        let mut rectangles = rectangles.into_iter();
+        let bounds = rectangles
+            .next()
+            .map(|first| rectangles.fold(first.clone(), |acc, r| acc.bounding_box(r)));
+        let (min, max) = bounds.map_or(([0.0; 2], [0.0; 2]), |b| {
+            (b.min.map(|x| x.as_()), b.max.map(|x| x.as_()))
+        });
+        let scale = if max[0] > min[0] {
+            width / (max[0] - min[0])
+        } else {
+            0.0
+        };
+        let height = (max[1] - min[1]) * scale;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.3} {height:.3}">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<rect width="{width:.3}" height="{height:.3}" fill="white"/>"#
+        )
+        .unwrap();
+        Canvas {
+            min,
+            max,
+            scale,
+            svg,
+        }
+    }
+
+    fn rect<F>(
+        &mut self,
+        rectangle: &Rectangle<F>,
+        fill: Option<[u8; 3]>,
+        stroke: [u8; 3],
+        stroke_width: f64,
+    ) where
+        F: 'static + Copy + AsPrimitive<f64>,
+    {
+        let x = (rectangle.min[0].as_() - self.min[0]) * self.scale;
+        let y = (self.max[1] - rectangle.max[1].as_()) * self.scale;
+        let w = (rectangle.max[0].as_() - rectangle.min[0].as_()) * self.scale;
+        let h = (rectangle.max[1].as_() - rectangle.min[1].as_()) * self.scale;
+        let fill = fill.map_or("none".to_string(), |[r, g, b]| format!("rgb({r},{g},{b})"));
+        let [r, g, b] = stroke;
+        writeln!(
+            self.svg,
+            r#"<rect x="{x:.3}" y="{y:.3}" width="{w:.3}" height="{h:.3}" fill="{fill}" stroke="rgb({r},{g},{b})" stroke-width="{stroke_width}"/>"#
+        )
+        .unwrap();
+    }
+
+    fn label<F>(&mut self, rectangle: &Rectangle<F>, text: usize, background: [u8; 3])
+    where
+        F: 'static + Copy + AsPrimitive<f64>,
+    {
+        let x =
+            ((rectangle.min[0].as_() + rectangle.max[0].as_()) / 2.0 - self.min[0]) * self.scale;
+        let y =
+            (self.max[1] - (rectangle.min[1].as_() + rectangle.max[1].as_()) / 2.0) * self.scale;
+        let size = (self.max[0] - self.min[0]).min(self.max[1] - self.min[1]) * self.scale / 20.0;
+        let brightness = background.iter().map(|&c| c as u32).sum::<u32>();
+        let color = if brightness > 3 * 128 {
+            "black"
+        } else {
+            "white"
+        };
+        writeln!(
+            self.svg,
+            r#"<text x="{x:.3}" y="{y:.3}" font-size="{size:.3}" font-family="sans-serif" text-anchor="middle" dominant-baseline="central" fill="{color}">{text}</text>"#
+        )
+        .unwrap();
+    }
+
+    fn finish(mut self) -> String {
+        self.svg.push_str("</svg>\n");
+        self.svg
+    }
+}
+
+/// Largest piece of a subdomain which is used to place its label
+fn largest_piece<F, S>(subdomain: &S) -> Option<&Rectangle<F>>
+where
+    F: Copy + PartialOrd + Num,
+    S: Subdomain<F, 2>,
+{
+    subdomain.pieces().iter().fold(None, |acc, p| match acc {
+        Some(a) if a.volume() >= p.volume() => Some(a),
+        _ => Some(p),
+    })
+}
+
+/// Renders the subdomains of a decomposition as SVG image.
+///
+/// Every subdomain is filled with a color of the `Bone` colormap according to its index and
+/// outlined in black, just like the plots of the `decompose_rectangles` example.
+/// Subdomains may consist of multiple pieces such as [CompositeDomain](crate::CompositeDomain)s.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 70.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 5.try_into().unwrap());
+/// let options = SvgOptions {
+///     labels: true,
+///     ..Default::default()
+/// };
+/// let svg = decomposition_svg(&subdomains, &options);
+/// // std::fs::write("decomposition.svg", svg)?;
+/// assert!(svg.starts_with("<svg"));
+/// assert_eq!(svg.matches("<text").count(), 5);
+/// ```
+pub fn decomposition_svg<F, S>(subdomains: &[S], options: &SvgOptions) -> String
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    let mut canvas = Canvas::new(subdomains.iter().flat_map(|s| s.pieces()), options.width);
+    let n_colors = subdomains.len() + 1;
+    for (n, subdomain) in subdomains.iter().enumerate() {
+        let color = bone((n + 1) as f64 / n_colors as f64);
+        for piece in subdomain.pieces() {
+            canvas.rect(piece, Some(color), [0; 3], 2.0);
+        }
+        if options.labels
+            && let Some(piece) = largest_piece(subdomain)
+        {
+            canvas.label(piece, n, color);
+        }
+    }
+    canvas.finish()
+}
+
+/// Renders the result of a digitization as SVG image.
+///
+/// Every digit is filled with the color of its subdomain and optionally outlined.
+/// Subdomains are outlined by thick black lines afterwards.
+/// This matches the plots of the `kmr_digitize_1` example.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0; 2],
+/// };
+/// let digits = kmr_decompose(&domain, 16.try_into().unwrap());
+/// let sorted = kmr_digitize_1(&domain, 3.try_into().unwrap(), digits.into_iter().enumerate())?;
+/// let svg = digitization_svg(&sorted, &SvgOptions::default());
+/// // Background, 16 digits and 3 subdomains
+/// assert_eq!(svg.matches("<rect").count(), 20);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn digitization_svg<F, I, S>(digits: &SortedDigits<F, I, S>, options: &SvgOptions) -> String
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+    S: Subdomain<F, 2>,
+{
+    let mut canvas = Canvas::new(
+        digits
+            .iter()
+            .flat_map(|(s, d)| s.pieces().iter().chain(d.iter().map(|(_, r)| r))),
+        options.width,
+    );
+    let n_subdomains = digits.len();
+    let mut colors = Vec::with_capacity(n_subdomains);
+    for (n, (_, digits)) in digits.iter().enumerate() {
+        let color = bone((n + 1) as f64 / n_subdomains as f64);
+        colors.push(color);
+        for (_, digit) in digits.iter() {
+            if options.digit_outlines {
+                canvas.rect(digit, Some(color), [30; 3], 1.0);
+            } else {
+                canvas.rect(digit, Some(color), color, 0.0);
+            }
+        }
+    }
+    for (n, (subdomain, _)) in digits.iter().enumerate() {
+        for piece in subdomain.pieces() {
+            canvas.rect(piece, None, [0; 3], 3.0);
+        }
+        if options.labels
+            && let Some(piece) = largest_piece(subdomain)
+        {
+            canvas.label(piece, n, colors[n]);
+        }
+    }
+    canvas.finish()
+}
+
+#[test]
+fn bone_matches_plotters() {
+    assert_eq!(bone(0.0), [0, 0, 0]);
+    assert_eq!(bone(0.5), [0, 0, 255]);
+    assert_eq!(bone(1.0), [255, 255, 255]);
+    assert_eq!(bone(0.25), [0, 0, 128]);
+    assert_eq!(bone(0.75), [128, 128, 255]);
+}
+
+#[test]
+fn decomposition_svg_coordinates() {
+    let subdomains = vec![
+        Rectangle {
+            min: [0.0, 0.0],
+            max: [2.0, 1.0],
+        },
+        Rectangle {
+            min: [0.0, 1.0],
+            max: [2.0, 4.0],
+        },
+    ];
+    let svg = decomposition_svg(
+        &subdomains,
+        &SvgOptions {
+            width: 100.0,
+            ..Default::default()
+        },
+    );
+    assert!(svg.contains(r#"width="100" height="200""#));
+    // The y-axis points upwards such that the first subdomain is drawn at the bottom
+    assert!(svg.contains(r#"<rect x="0.000" y="150.000" width="100.000" height="50.000""#));
+    assert!(svg.contains(r#"<rect x="0.000" y="0.000" width="100.000" height="150.000""#));
+    assert!(!svg.contains("<text"));
+    assert!(svg.ends_with("</svg>\n"));
+}
+
+#[test]
+fn digitization_svg_outlines() {
+    let domain = Rectangle {
+        min: [0.0; 2],
+        max: [4.0; 2],
+    };
+    let digits = (0..16).map(|n| {
+        let min = [(n % 4) as f64, (n / 4) as f64];
+        (
+            n,
+            Rectangle {
+                min,
+                max: [min[0] + 1.0, min[1] + 1.0],
+            },
+        )
+    });
+    let (left, right) = domain.split(0, 2.0).unwrap();
+    let sorted: SortedDigits<f64, usize> = crate::digitize([left, right], digits).unwrap();
+    let with_outlines = digitization_svg(&sorted, &SvgOptions::default());
+    assert_eq!(with_outlines.matches("rgb(30,30,30)").count(), 16);
+    let options = SvgOptions {
+        labels: true,
+        digit_outlines: false,
+        ..Default::default()
+    };
+    let without_outlines = digitization_svg(&sorted, &options);
+    assert_eq!(without_outlines.matches("rgb(30,30,30)").count(), 0);
+    assert_eq!(without_outlines.matches("<text").count(), 2);
+}
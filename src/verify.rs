@@ -0,0 +1,207 @@
+use num_traits::Num;
+
+use crate::{Cuboid, KdTree, Subdomain};
+
+/// Violation of a partition found by [verify_partition]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Violation<F> {
+    /// The subdomain with the given index is not contained in the domain
+    Outside(usize),
+    /// The subdomains with the given indices overlap by the given area
+    ///
+    /// Both indices are equal if pieces of the same subdomain overlap.
+    Overlap(usize, usize, F),
+    /// The given area of the domain is not covered by any subdomain
+    Gap(F),
+}
+
+/// Checks that the given subdomains partition the domain.
+///
+/// Subdomains may be given by any [Subdomain] in any dimension such that the output of every
+/// decomposition method can be verified.
+/// The following violations are reported:
+/// 1. Subdomains which are not contained in the domain
+/// 2. Pairs of subdomains whose intersection is wider than `epsilon` along every axis, where a
+///    subdomain is paired with itself if two of its pieces intersect
+/// 3. The area of the domain which is not covered
+///
+/// Coordinates may deviate by up to `epsilon`.
+/// Accordingly, gaps are only reported if they are larger than `epsilon` times half the sum of
+/// all perimeters (surfaces in `3D`).
+/// Integer coordinates can be verified exactly with `epsilon = 0`.
+/// Returns an empty list if the subdomains form a valid partition.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 70.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 7.try_into().unwrap());
+/// assert!(verify_partition(&domain, &subdomains, 1e-10).is_empty());
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [4.0, 2.0],
+/// };
+/// let subdomains = [
+///     Rectangle {
+///         min: [0.0; 2],
+///         max: [2.0; 2],
+///     },
+///     Rectangle {
+///         min: [1.0, 0.0],
+///         max: [4.0, 1.0],
+///     },
+/// ];
+/// assert_eq!(
+///     verify_partition(&domain, &subdomains, 1e-10),
+///     vec![Violation::Overlap(0, 1, 1.0), Violation::Gap(2.0)]
+/// );
+/// ```
+pub fn verify_partition<F, S, const D: usize>(
+    domain: &Cuboid<F, D>,
+    subdomains: &[S],
+    epsilon: F,
+) -> Vec<Violation<F>>
+where
+    F: Copy + PartialOrd + Num,
+    S: Subdomain<F, D>,
+{
+    let mut violations = Vec::new();
+    let bounds = domain.expand(epsilon);
+    for (n, subdomain) in subdomains.iter().enumerate() {
+        if !subdomain.pieces().iter().all(|p| bounds.contains_cuboid(p)) {
+            violations.push(Violation::Outside(n));
+        }
+    }
+
+    let mut owners = Vec::new();
+    let tree = KdTree::new(subdomains.iter().enumerate().flat_map(|(n, subdomain)| {
+        owners.extend(std::iter::repeat_n(n, subdomain.pieces().len()));
+        subdomain.pieces().iter().cloned()
+    }));
+    let mut overlaps: Vec<(usize, usize, F)> = Vec::new();
+    let mut overlap_area = F::zero();
+    for (i, piece) in tree.cuboids().iter().enumerate() {
+        for j in tree.intersecting(piece) {
+            let (n, m) = (owners[i], owners[j]);
+            if (n, i) >= (m, j) {
+                continue;
+            }
+            let Some(intersection) = piece.intersection(&tree.cuboids()[j]) else {
+                continue;
+            };
+            if intersection.lengths().iter().any(|&l| l <= epsilon) {
+                continue;
+            }
+            let area = intersection.volume();
+            overlap_area = overlap_area + area;
+            match overlaps.iter_mut().find(|(a, b, _)| (*a, *b) == (n, m)) {
+                Some((_, _, total)) => *total = *total + area,
+                None => overlaps.push((n, m, area)),
+            }
+        }
+    }
+    overlaps.sort_by_key(|&(n, m, _)| (n, m));
+    violations.extend(
+        overlaps
+            .into_iter()
+            .map(|(n, m, a)| Violation::Overlap(n, m, a)),
+    );
+
+    // Area of the domain covered by subdomains where overlaps are counted only once
+    let two = F::one() + F::one();
+    let (covered, tolerance) =
+        tree.cuboids()
+            .iter()
+            .fold((F::zero(), F::zero()), |(covered, tolerance), piece| {
+                let inside = piece.intersection(domain).map_or(F::zero(), |i| i.volume());
+                (
+                    covered + inside,
+                    tolerance + epsilon * piece.surface() / two,
+                )
+            });
+    if covered + tolerance < domain.volume() + overlap_area {
+        violations.push(Violation::Gap(domain.volume() + overlap_area - covered));
+    }
+    violations
+}
+
+#[test]
+fn verify_partition_composite() {
+    let domain = crate::Rectangle {
+        min: [0.0; 2],
+        max: [100.0; 2],
+    };
+    let obstacles = [crate::Rectangle {
+        min: [40.0; 2],
+        max: [60.0; 2],
+    }];
+    let subdomains =
+        crate::decompose_with_holes(&domain, &obstacles, 5.try_into().unwrap()).unwrap();
+    assert_eq!(
+        verify_partition(&domain, &subdomains, 1e-10),
+        vec![Violation::Gap(400.0)]
+    );
+    let mut pieces: Vec<_> = subdomains
+        .iter()
+        .flat_map(|s| s.pieces())
+        .cloned()
+        .collect();
+    pieces.extend(obstacles);
+    assert!(verify_partition(&domain, &pieces, 1e-10).is_empty());
+}
+
+#[test]
+fn verify_partition_overlapping_pieces() {
+    struct Pieces(Vec<crate::Rectangle<i32>>);
+    impl Subdomain<i32, 2> for Pieces {
+        fn pieces(&self) -> &[crate::Rectangle<i32>] {
+            &self.0
+        }
+    }
+    let domain = crate::Rectangle {
+        min: [0; 2],
+        max: [4, 2],
+    };
+    let subdomains = [
+        Pieces(vec![
+            crate::Rectangle {
+                min: [0; 2],
+                max: [2; 2],
+            },
+            crate::Rectangle {
+                min: [1, 0],
+                max: [3, 2],
+            },
+        ]),
+        Pieces(vec![crate::Rectangle {
+            min: [3, 0],
+            max: [4, 2],
+        }]),
+    ];
+    assert_eq!(
+        verify_partition(&domain, &subdomains, 0),
+        vec![Violation::Overlap(0, 0, 2)]
+    );
+}
+
+#[test]
+fn verify_partition_integer() {
+    let domain = crate::Rectangle {
+        min: [0i32; 2],
+        max: [64, 48],
+    };
+    let mut subdomains = crate::kmr_decompose_integer(&domain, 6.try_into().unwrap());
+    assert!(verify_partition(&domain, &subdomains, 0).is_empty());
+    subdomains[5].max[1] += 1;
+    subdomains[0].min[0] += 2;
+    let violations = verify_partition(&domain, &subdomains, 0);
+    assert_eq!(violations[0], Violation::Outside(5));
+    assert!(matches!(violations.last(), Some(Violation::Gap(_))));
+    // Deviations within the tolerance are accepted
+    assert_eq!(verify_partition(&domain, &subdomains, 2), vec![]);
+}
@@ -0,0 +1,201 @@
+use std::io::Write;
+
+use num_traits::{AsPrimitive, Num};
+
+use crate::{Cuboid, Error, Result};
+
+/// Writes the given subdomains as VTK unstructured grid (`.vtu`) in ASCII format.
+///
+/// Every subdomain is stored as a separate cell of type `VTK_LINE` (`1D`), `VTK_PIXEL` (`2D`) or
+/// `VTK_VOXEL` (`3D`) such that the file can be opened directly in
+/// [ParaView](https://www.paraview.org).
+/// The cell data contains the index of every subdomain, its measure (`length`, `area` or
+/// `volume`) and optionally its load.
+/// The output is buffered internally such that a [File](std::fs::File) can be passed directly.
+///
+/// Returns [Error::Dimension] for more than three dimensions and [Error::Load] if the number of
+/// loads does not match the number of subdomains.
+///
+/// ```
+/// use spatial_decomposition::*;
+///
+/// let domain = Rectangle {
+///     min: [0.0; 2],
+///     max: [100.0, 50.0],
+/// };
+/// let subdomains = kmr_decompose(&domain, 4.try_into().unwrap());
+/// let loads = [1.0, 2.0, 3.0, 4.0];
+///
+/// let mut file = Vec::new(); // or std::fs::File::create("decomposition.vtu")?
+/// write_vtu(&mut file, &subdomains, Some(&loads))?;
+///
+/// let vtu = String::from_utf8(file).unwrap();
+/// assert!(vtu.contains(r#"<Piece NumberOfPoints="16" NumberOfCells="4">"#));
+/// assert!(vtu.contains(r#"Name="load""#));
+/// # Ok::<(), Error>(())
+/// ```
+pub fn write_vtu<F, const D: usize>(
+    writer: impl Write,
+    subdomains: &[Cuboid<F, D>],
+    loads: Option<&[F]>,
+) -> Result<()>
+where
+    F: 'static + Copy + PartialOrd + Num + AsPrimitive<f64>,
+{
+    let (cell_type, measure) = match D {
+        1 => (3, "length"),
+        2 => (8, "area"),
+        3 => (11, "volume"),
+        _ => return Err(Error::Dimension),
+    };
+    if loads.is_some_and(|l| l.len() != subdomains.len()) {
+        return Err(Error::Load);
+    }
+    let n_corners = 1 << D;
+    let mut writer = std::io::BufWriter::new(writer);
+    let w = &mut writer;
+
+    writeln!(w, r#"<?xml version="1.0"?>"#)?;
+    writeln!(
+        w,
+        r#"<VTKFile type="UnstructuredGrid" version="1.0" byte_order="LittleEndian">"#
+    )?;
+    writeln!(w, "  <UnstructuredGrid>")?;
+    writeln!(
+        w,
+        r#"    <Piece NumberOfPoints="{}" NumberOfCells="{}">"#,
+        n_corners * subdomains.len(),
+        subdomains.len()
+    )?;
+
+    writeln!(w, "      <Points>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="Float64" NumberOfComponents="3" format="ascii">"#
+    )?;
+    for corner in subdomains.iter().flat_map(|s| s.corners()) {
+        let point: [f64; 3] = std::array::from_fn(|i| if i < D { corner[i].as_() } else { 0.0 });
+        writeln!(w, "          {} {} {}", point[0], point[1], point[2])?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    writeln!(w, "      </Points>")?;
+
+    writeln!(w, "      <Cells>")?;
+    writeln!(
+        w,
+        r#"        <DataArray type="Int64" Name="connectivity" format="ascii">"#
+    )?;
+    for n in 0..subdomains.len() {
+        let ids: Vec<_> = (n * n_corners..(n + 1) * n_corners)
+            .map(|i| i.to_string())
+            .collect();
+        writeln!(w, "          {}", ids.join(" "))?;
+    }
+    writeln!(w, "        </DataArray>")?;
+    write_array(
+        w,
+        "Int64",
+        "offsets",
+        (1..=subdomains.len()).map(|n| n * n_corners),
+    )?;
+    write_array(w, "UInt8", "types", subdomains.iter().map(|_| cell_type))?;
+    writeln!(w, "      </Cells>")?;
+
+    writeln!(w, r#"      <CellData Scalars="subdomain">"#)?;
+    write_array(w, "Int64", "subdomain", 0..subdomains.len())?;
+    write_array(
+        w,
+        "Float64",
+        measure,
+        subdomains.iter().map(|s| s.volume().as_()),
+    )?;
+    if let Some(loads) = loads {
+        write_array(w, "Float64", "load", loads.iter().map(|l| l.as_()))?;
+    }
+    writeln!(w, "      </CellData>")?;
+
+    writeln!(w, "    </Piece>")?;
+    writeln!(w, "  </UnstructuredGrid>")?;
+    writeln!(w, "</VTKFile>")?;
+    w.flush()?;
+    Ok(())
+}
+
+fn write_array<T: std::fmt::Display>(
+    w: &mut impl Write,
+    data_type: &str,
+    name: &str,
+    values: impl IntoIterator<Item = T>,
+) -> std::io::Result<()> {
+    writeln!(
+        w,
+        r#"        <DataArray type="{data_type}" Name="{name}" format="ascii">"#
+    )?;
+    for value in values {
+        writeln!(w, "          {value}")?;
+    }
+    writeln!(w, "        </DataArray>")
+}
+
+#[cfg(test)]
+fn parse_vtu_array(vtu: &str, name: &str) -> Vec<f64> {
+    let start = vtu.find(&format!(r#"Name="{name}""#)).unwrap();
+    let body = &vtu[start..];
+    let body = &body[body.find('>').unwrap() + 1..body.find("</DataArray>").unwrap()];
+    body.split_whitespace()
+        .map(|x| x.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn write_vtu_3d() {
+    let cuboids: Vec<_> = (0..3)
+        .map(|n| Cuboid {
+            min: [n, 0, 0],
+            max: [n + 1, 2, 3],
+        })
+        .collect();
+    let mut file = Vec::new();
+    write_vtu(&mut file, &cuboids, None).unwrap();
+    let vtu = String::from_utf8(file).unwrap();
+    assert!(vtu.contains(r#"NumberOfPoints="24" NumberOfCells="3""#));
+    assert!(!vtu.contains(r#"Name="load""#));
+    assert_eq!(parse_vtu_array(&vtu, "volume"), vec![6.0; 3]);
+    assert_eq!(parse_vtu_array(&vtu, "offsets"), vec![8.0, 16.0, 24.0]);
+    assert_eq!(parse_vtu_array(&vtu, "types"), vec![11.0; 3]);
+    assert_eq!(parse_vtu_array(&vtu, "subdomain"), vec![0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn write_vtu_2d_loads() {
+    let domain = crate::Rectangle {
+        min: [-1.0, 2.0],
+        max: [99.0, 72.0],
+    };
+    let subdomains = crate::kmr_decompose(&domain, 7.try_into().unwrap());
+    let loads: Vec<_> = (0..7).map(|n| n as f64 * 0.5).collect();
+    let mut file = Vec::new();
+    write_vtu(&mut file, &subdomains, Some(&loads)).unwrap();
+    let vtu = String::from_utf8(file).unwrap();
+    assert_eq!(parse_vtu_array(&vtu, "load"), loads);
+    let areas = parse_vtu_array(&vtu, "area");
+    approx::assert_relative_eq!(areas.iter().sum::<f64>(), 7000.0, max_relative = 1e-12);
+
+    // Points are padded with zeros in the third component
+    let start = vtu.find("NumberOfComponents").unwrap();
+    let line = vtu[start..].lines().nth(1).unwrap();
+    assert_eq!(line.trim(), "-1 2 0");
+
+    assert!(matches!(
+        write_vtu(Vec::new(), &subdomains, Some(&loads[1..])),
+        Err(Error::Load)
+    ));
+    let hyper = [Cuboid {
+        min: [0.0; 4],
+        max: [1.0; 4],
+    }];
+    assert!(matches!(
+        write_vtu(Vec::new(), &hyper, None),
+        Err(Error::Dimension)
+    ));
+}